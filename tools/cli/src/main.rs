@@ -7,7 +7,10 @@ use std::{path::PathBuf, process::exit};
 use anyhow::anyhow;
 use clap::{Parser, Subcommand};
 use libra_config::config_cli::ConfigCli;
-use libra_query::query_cli::QueryCli;
+use libra_query::{
+    query_cli::QueryCli,
+    query_error::{report_error, QueryExit},
+};
 use libra_txs::txs_cli::TxsCli;
 use libra_wallet::wallet_cli::WalletCli;
 use node_cli::NodeCli;
@@ -81,14 +84,22 @@ fn main() -> anyhow::Result<()> {
                     // Execute Query CLI subcommand
                     Some(Sub::Query(query_cli)) => {
                         if let Err(e) = query_cli.run().await {
+                            if query_cli.error_json {
+                                exit(report_error(&e, true));
+                            }
                             eprintln!("Failed to execute query tool, message: {}", &e);
+                            exit(QueryExit::from_error(&e).code());
                         }
                     }
 
                     // Execute Transactions CLI subcommand
                     Some(Sub::Txs(txs_cli)) => {
                         if let Err(e) = txs_cli.run().await {
+                            if txs_cli.error_json {
+                                exit(report_error(&e, true));
+                            }
                             eprintln!("Failed to execute txs tool, message: {}", &e);
+                            exit(QueryExit::from_error(&e).code());
                         }
                     }
 
@@ -28,7 +28,13 @@ impl GenesisCli {
         let chain_name = self.chain.unwrap_or(NamedChain::TESTNET); // chain_id = 2
 
         match &self.command {
-            Some(Sub::Build { github, drop_list }) => {
+            Some(Sub::Build {
+                github,
+                drop_list,
+                no_supply_report,
+                future_uses_ratio,
+                dry_run,
+            }) => {
                 let mut recovery = if let Some(p) = github.json_legacy.clone() {
                     parse_json::recovery_file_parse(p)?
                 } else {
@@ -41,7 +47,11 @@ impl GenesisCli {
 
                 // TODO: there's no reason a github token should be needed to
                 // read the genesis
-                let github_token = fs::read_to_string(data_path.join(GITHUB_TOKEN_FILENAME))?;
+                let github_token = if *dry_run {
+                    String::new()
+                } else {
+                    fs::read_to_string(data_path.join(GITHUB_TOKEN_FILENAME))?
+                };
 
                 genesis_builder::build(
                     github.org_github.to_owned(),
@@ -52,6 +62,9 @@ impl GenesisCli {
                     &mut recovery,
                     chain_name,
                     None,
+                    !no_supply_report,
+                    *future_uses_ratio,
+                    *dry_run,
                 )?;
             }
             Some(Sub::Register { github }) => {
@@ -75,6 +88,7 @@ impl GenesisCli {
                 host_list,
                 framework_mrb_path,
                 json_legacy,
+                force,
             }) => {
                 testnet_setup::setup(
                     me,
@@ -83,6 +97,7 @@ impl GenesisCli {
                     data_path,
                     json_legacy.to_owned(),
                     Some(framework_mrb_path.to_owned()),
+                    *force,
                 )
                 .await?
             }
@@ -124,6 +139,20 @@ enum Sub {
         /// Ark B
         #[clap(long)]
         drop_list: Option<PathBuf>,
+
+        /// skip writing genesis_supply_report.json next to the genesis blob
+        #[clap(long)]
+        no_supply_report: bool,
+
+        /// share of the supply reserved for future uses, e.g. 0.7, adds the
+        /// infra escrow split to the supply report
+        #[clap(long)]
+        future_uses_ratio: Option<f64>,
+
+        /// print the supply report as json, and the escrow split with
+        /// --future-uses-ratio, without writing the genesis blob
+        #[clap(long)]
+        dry_run: bool,
     }, // just do genesis without wizard
     /// register to the genesis coordination git repository
     Register {
@@ -133,12 +162,12 @@ enum Sub {
     },
 
     /// sensible defaults for testnet, does not need a genesis repo
-    /// accounts are created from fixture mnemonics for alice, bob, carol, dave
+    /// accounts are created from fixture mnemonics for alice, bob, carol, dave, eve, frank
     Testnet {
         /// which persona is this machine going to register as
         #[clap(short, long)]
         me: TestPersona,
-        /// ordered list of dns/ip with port for alice..dave
+        /// ordered list of dns/ip with port for alice..frank
         /// use 6180 for production validator port
         #[clap(long)]
         host_list: Vec<HostAndPort>,
@@ -148,5 +177,8 @@ enum Sub {
         /// path to file for legacy migration file
         #[clap(short, long)]
         json_legacy: Option<PathBuf>,
+        /// delete an existing data directory without asking
+        #[clap(long)]
+        force: bool,
     },
 }
@@ -76,7 +76,18 @@ pub fn build(
     legacy_recovery: &mut [LegacyRecoveryV6],
     chain_name: NamedChain,
     testnet_vals: Option<Vec<ValidatorConfiguration>>,
+    supply_report: bool,
+    future_uses_ratio: Option<f64>,
+    dry_run: bool,
 ) -> Result<Vec<PathBuf>> {
+    // print the numbers for review, before anything is written
+    if dry_run {
+        let s = supply::populate_supply_stats_from_legacy(legacy_recovery)?;
+        let escrow = future_uses_ratio.map(|r| supply::compute_infra_escrow(&s, r));
+        println!("{}", supply::supply_report_json(&s, escrow.as_ref())?);
+        return Ok(vec![]);
+    }
+
     let output_dir = home_path.join("genesis");
     std::fs::create_dir_all(&output_dir)?;
 
@@ -160,13 +171,22 @@ pub fn build(
         output_dir.to_str().unwrap()
     ));
 
+    // the same recovery set which was encoded in the genesis tx above
+    let s = supply::populate_supply_stats_from_legacy(legacy_recovery)?;
+
+    let mut output_files = vec![genesis_file, waypoint_file];
+    if supply_report {
+        let escrow = future_uses_ratio.map(|r| supply::compute_infra_escrow(&s, r));
+        let report = supply::write_supply_report(&s, escrow.as_ref(), &output_dir)?;
+        OLProgress::complete(&format!("supply report saved to {}", report.display()));
+        output_files.push(report);
+    }
+
     // Audits the generated genesis.blob comparing to the JSON input.
     if !legacy_recovery.is_empty() {
         // get a boostrapped DB to do audits
         let (db_rw, _) = bootstrap_db_reader_from_gen_tx(gen_info.get_genesis())?;
 
-        let s = supply::populate_supply_stats_from_legacy(legacy_recovery)?;
-
         compare::compare_recovery_vec_to_genesis_tx(legacy_recovery, &db_rw.reader, &s)?;
         OLProgress::complete("account balances as expected");
 
@@ -177,7 +197,7 @@ pub fn build(
     }
 
     OLProgress::complete("LFG, ready for genesis");
-    Ok(vec![genesis_file, waypoint_file])
+    Ok(output_files)
 }
 
 /// there are two structs called GenesisConfiguration in Vendor code, sigh.
@@ -586,6 +606,9 @@ fn test_build() {
         &mut [],
         NamedChain::TESTING,
         None,
+        true,
+        None,
+        false,
     )
     .unwrap();
 }
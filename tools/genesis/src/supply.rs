@@ -1,100 +1,388 @@
+use diem_logger::prelude::*;
 use indicatif::ProgressBar;
 use libra_backwards_compatibility::legacy_recovery_v6::LegacyRecoveryV6;
-use libra_types::ol_progress::OLProgress;
-use std::time::Duration;
+use libra_types::{exports::AccountAddress, ol_progress::OLProgress};
+use serde::Serialize;
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-#[derive(Debug, Clone, Default)]
+/// file name of the supply report written next to the genesis blob
+pub const SUPPLY_REPORT_FILE: &str = "genesis_supply_report.json";
+
+/// Coin totals are integers of the on chain base unit, so that sums of the
+/// whole ledger stay exact. Floats are only used for ratios.
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Supply {
-    pub total: f64,
-    pub normal: f64,
-    pub validator: f64, // will overlap with slow wallet
-    pub slow_total: f64,
-    pub slow_locked: f64,
-    pub slow_validator_locked: f64,
-    pub slow_unlocked: f64,
-    pub donor_voice: f64,
-    pub make_whole: f64,
+    pub total: u128,
+    pub normal: u128,
+    pub validator: u128, // will overlap with slow wallet
+    pub slow_total: u128,
+    pub slow_locked: u128,
+    pub slow_validator_locked: u128,
+    pub slow_unlocked: u128,
+    pub donor_voice: u128,
+    pub make_whole: u128,
     // which will compute later
     pub split_factor: f64,
     pub escrow_pct: f64,
-    pub epoch_reward_base_case: f64,
-    pub expected_user_balance: f64,
+    pub epoch_reward_base_case: u128,
+    pub expected_user_balance: u128,
     pub expected_user_ratio: f64,
-    pub expected_circulating: f64,
+    pub expected_circulating: u128,
     pub expected_circulating_ratio: f64,
+    /// false if the recovery had no community wallets, so donor_voice is zero
+    pub comm_wallets_found: bool,
 }
 
-impl Supply {
-    fn inc_supply(&mut self, r: &LegacyRecoveryV6) -> &mut Self {
+/// How an account's balance is counted in the supply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SupplyCategory {
+    Normal,
+    Slow,
+    /// a slow wallet with a validator config and a locked balance
+    Validator,
+    DonorDirected,
+}
+
+/// One account's share of the supply, as summed by `Supply`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountSupply {
+    pub address: String,
+    pub balance: u128,
+    pub category: SupplyCategory,
+    pub slow_locked: u128,
+    pub slow_unlocked: u128,
+}
+
+impl AccountSupply {
+    /// classify a recovery account, the one place supply categories are decided
+    pub fn from_recovery(r: &LegacyRecoveryV6, dd_wallets: &[AccountAddress]) -> Self {
         // get balances
-        let user_total: f64 = match &r.balance {
-            Some(b) => b.coin as f64,
-            None => 0.0,
+        let user_total: u128 = match &r.balance {
+            Some(b) => b.coin as u128,
+            None => 0,
+        };
+        let mut a = AccountSupply {
+            address: r.account.map(|a| a.to_hex_literal()).unwrap_or_default(),
+            balance: user_total,
+            category: SupplyCategory::Normal,
+            slow_locked: 0,
+            slow_unlocked: 0,
         };
-        self.total += user_total;
 
-        // sum all accounts
-        if let Some(sl) = &r.slow_wallet {
+        if r.account.map_or(false, |a| dd_wallets.contains(&a)) {
+            // donor directed wallets are counted apart, even if they are also slow
+            a.category = SupplyCategory::DonorDirected;
+        } else if let Some(sl) = &r.slow_wallet {
             // is it a slow wallet?
-            self.slow_total += user_total;
+            a.category = SupplyCategory::Slow;
             if sl.unlocked > 0 {
+                let unlocked = sl.unlocked as u128;
                 // safety check, the unlocked should always be lower than total balance
-                if user_total > sl.unlocked as f64 {
-                    self.slow_unlocked += sl.unlocked as f64;
+                if user_total > unlocked {
+                    a.slow_unlocked = unlocked;
                     // Note: the validator may have transferred everything out, and the unlocked may not have changed
-                    let locked = user_total - sl.unlocked as f64;
-                    self.slow_locked += locked;
+                    a.slow_locked = user_total - unlocked;
                     // if this is the special case of a validator account with slow locked balance
                     if r.val_cfg.is_some() {
-                        self.validator += user_total;
-                        self.slow_validator_locked += locked;
+                        a.category = SupplyCategory::Validator;
                     }
                 } else {
                     // we shouldn't have more unlocked coins than the actual balance
-                    self.slow_unlocked += user_total;
+                    a.slow_unlocked = user_total;
                 }
             }
         } else if r.cumulative_deposits.is_some() {
             // catches the cases of any dd wallets that were mapped to slow wallets
-            self.slow_locked += user_total;
-            self.slow_total += user_total;
-        } else {
-            self.normal += user_total;
+            a.category = SupplyCategory::Slow;
+            a.slow_locked = user_total;
+        }
+        a
+    }
+}
+
+impl Supply {
+    fn inc_supply(&mut self, a: &AccountSupply) -> &mut Self {
+        self.total += a.balance;
+
+        match a.category {
+            SupplyCategory::Normal => self.normal += a.balance,
+            SupplyCategory::DonorDirected => self.donor_voice += a.balance,
+            SupplyCategory::Slow | SupplyCategory::Validator => {
+                self.slow_total += a.balance;
+                self.slow_locked += a.slow_locked;
+                self.slow_unlocked += a.slow_unlocked;
+                // validators overlap with the slow wallets
+                if a.category == SupplyCategory::Validator {
+                    self.validator += a.balance;
+                    self.slow_validator_locked += a.slow_locked;
+                }
+            }
         }
         self
     }
+
+    /// The share of the total supply as a percentage, for display only.
+    pub fn pct_of_total(&self, part: u128) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        part as f64 / self.total as f64 * 100.0
+    }
+}
+
+/// How much of the validators' locked slow wallet balances move to the
+/// infrastructure escrow at genesis.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EscrowSplit {
+    /// coins moved from the validators' locked balances to the escrow
+    pub to_escrow: u128,
+    /// the slow wallet total after the escrow is taken out
+    pub new_slow_total: u128,
+    /// share of each validator's locked balance which goes to escrow
+    pub ratio: f64,
+    /// the locked validator balances the ratio applies to
+    slow_validator_locked: u128,
+}
+
+impl EscrowSplit {
+    /// The escrow taken from one validator's locked balance, rounded down.
+    pub fn escrow_for(&self, locked: u128) -> u128 {
+        if self.slow_validator_locked == 0 {
+            return 0;
+        }
+        locked * self.to_escrow / self.slow_validator_locked
+    }
+}
+
+/// Split the supply so that `target_future_uses_pct` (a fraction, e.g. 0.7)
+/// of the total is reserved for future uses. Donor voice accounts already
+/// count toward the target, the rest is funded from the validators' locked
+/// slow wallet balances, up to all of it.
+pub fn compute_infra_escrow(supply: &Supply, target_future_uses_pct: f64) -> EscrowSplit {
+    // keep the ledger math in integers, the percentage is applied as parts per million
+    let ppm = (target_future_uses_pct.clamp(0.0, 1.0) * 1_000_000.0).round() as u128;
+    let target_future_uses = supply.total * ppm / 1_000_000;
+    let remaining_to_fund = target_future_uses.saturating_sub(supply.donor_voice);
+
+    let to_escrow = remaining_to_fund.min(supply.slow_validator_locked);
+    let ratio = if supply.slow_validator_locked == 0 {
+        0.0
+    } else {
+        to_escrow as f64 / supply.slow_validator_locked as f64
+    };
+
+    let split = EscrowSplit {
+        to_escrow,
+        new_slow_total: supply.slow_total - to_escrow,
+        ratio,
+        slow_validator_locked: supply.slow_validator_locked,
+    };
+
+    // the escrow only moves coins, it never creates or destroys them
+    assert_eq!(split.new_slow_total + split.to_escrow, supply.slow_total);
+    assert!(split.to_escrow <= supply.slow_validator_locked);
+    split
 }
 
 /// iterate over the recovery file and get the sum of all balances.
 /// there's an option to map certain donor-directed wallets to be counted as slow wallets
 /// Note: this may not be the "total supply", since there may be coins in other structs beside an account::balance, e.g escrowed in contracts.
 pub fn populate_supply_stats_from_legacy(rec: &[LegacyRecoveryV6]) -> anyhow::Result<Supply> {
+    let (supply, _) = populate_supply_stats_with_breakdown(rec)?;
+    Ok(supply)
+}
+
+/// The supply stats, and the classification of each account which was
+/// summed into them, in the order of the recovery.
+pub fn populate_supply_stats_with_breakdown(
+    rec: &[LegacyRecoveryV6],
+) -> anyhow::Result<(Supply, Vec<AccountSupply>)> {
     let pb = ProgressBar::new(1000)
         .with_style(OLProgress::spinner())
         .with_message("calculating coin supply");
     pb.enable_steady_tick(Duration::from_millis(100));
-    let mut supply = Supply {
-        total: 0.0,
-        normal: 0.0,
-        validator: 0.0,
-        slow_total: 0.0,
-        slow_locked: 0.0,
-        slow_validator_locked: 0.0,
-        slow_unlocked: 0.0,
-        donor_voice: 0.0,
-        make_whole: 0.0,
-        split_factor: 0.0,
-        escrow_pct: 0.0,
-        epoch_reward_base_case: 0.0,
-        expected_user_balance: 0.0,
-        expected_user_ratio: 0.0,
-        expected_circulating: 0.0,
-        expected_circulating_ratio: 0.0,
-    };
+    let mut supply = Supply::default();
 
-    rec.iter().for_each(|r| {
-        supply.inc_supply(r);
+    let dd_wallets = get_donor_directed_list(rec);
+    if dd_wallets.is_empty() {
+        warn!("no community wallets found in recovery, donor voice supply will be zero");
+    }
+    supply.comm_wallets_found = !dd_wallets.is_empty();
+
+    let breakdown: Vec<AccountSupply> = rec
+        .iter()
+        .map(|r| AccountSupply::from_recovery(r, &dd_wallets))
+        .collect();
+    breakdown.iter().for_each(|a| {
+        supply.inc_supply(a);
     });
     pb.finish_and_clear();
-    Ok(supply)
+    Ok((supply, breakdown))
+}
+
+/// The donor directed (community) wallets in the recovery. Each community
+/// wallet carries the comm_wallet resource, a recovery from a chain which
+/// never had any gives an empty list.
+pub fn get_donor_directed_list(rec: &[LegacyRecoveryV6]) -> Vec<AccountAddress> {
+    rec.iter()
+        .filter(|r| r.comm_wallet.is_some())
+        .filter_map(|r| r.account)
+        .collect()
+}
+
+/// Write one csv row per account of the recovery, with the category it is
+/// counted under in the supply stats. Auditors can diff this against the
+/// chain state after genesis to find misclassified accounts.
+pub fn export_supply_breakdown(rec: &[LegacyRecoveryV6], out: &Path) -> anyhow::Result<()> {
+    let (_, breakdown) = populate_supply_stats_with_breakdown(rec)?;
+    let mut wtr = csv::Writer::from_path(out)?;
+    for a in breakdown {
+        wtr.serialize(a)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct SupplyReport<'a> {
+    #[serde(flatten)]
+    supply: &'a Supply,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    escrow: Option<&'a EscrowSplit>,
+}
+
+/// Writes the supply stats of the recovery set which went into the genesis
+/// blob, so that ceremony participants can review the distribution.
+/// The infra escrow split is included when the ceremony set a future uses
+/// target.
+pub fn write_supply_report(
+    supply: &Supply,
+    escrow: Option<&EscrowSplit>,
+    output_dir: &Path,
+) -> anyhow::Result<PathBuf> {
+    let path = output_dir.join(SUPPLY_REPORT_FILE);
+    std::fs::write(&path, supply_report_json(supply, escrow)?)?;
+    Ok(path)
+}
+
+/// The supply report as pretty json, the same on every machine given the
+/// same recovery file, so participants can diff their runs.
+pub fn supply_report_json(supply: &Supply, escrow: Option<&EscrowSplit>) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(&SupplyReport {
+        supply,
+        escrow,
+    })?)
+}
+
+#[test]
+fn supply_sums_are_exact_past_f64_precision() {
+    use libra_types::move_resource::cumulative_deposits::LegacyBalanceResourceV6;
+
+    // 2^53 + 1 cannot be represented by an f64
+    let big = (1u64 << 53) + 1;
+    let rec: Vec<LegacyRecoveryV6> = [big, 1, 1]
+        .iter()
+        .map(|coin| LegacyRecoveryV6 {
+            balance: Some(LegacyBalanceResourceV6 { coin: *coin }),
+            ..Default::default()
+        })
+        .collect();
+
+    let s = populate_supply_stats_from_legacy(&rec).unwrap();
+    assert_eq!(s.total, big as u128 + 2);
+    assert_eq!(s.normal, s.total);
+}
+
+#[test]
+fn supply_breakdown_matches_totals() {
+    use libra_types::move_resource::{
+        cumulative_deposits::LegacyBalanceResourceV6,
+        wallet::{CommunityWalletsResource, SlowWalletResource},
+    };
+
+    let account = |n: u64, coin: u64| LegacyRecoveryV6 {
+        account: Some(AccountAddress::from_hex_literal(&format!("0x{n}")).unwrap()),
+        balance: Some(LegacyBalanceResourceV6 { coin }),
+        ..Default::default()
+    };
+    let normal = account(1, 10);
+    let slow = LegacyRecoveryV6 {
+        slow_wallet: Some(SlowWalletResource {
+            unlocked: 40,
+            transferred: 0,
+        }),
+        ..account(2, 100)
+    };
+    let dd = LegacyRecoveryV6 {
+        comm_wallet: Some(CommunityWalletsResource { list: vec![] }),
+        ..account(3, 50)
+    };
+    let rec = vec![normal, slow, dd];
+
+    let temp = diem_temppath::TempPath::new();
+    export_supply_breakdown(&rec, temp.path()).unwrap();
+    let csv = std::fs::read_to_string(temp.path()).unwrap();
+    assert_eq!(
+        csv,
+        "address,balance,category,slow_locked,slow_unlocked\n\
+         0x1,10,normal,0,0\n\
+         0x2,100,slow,60,40\n\
+         0x3,50,donor_directed,0,0\n"
+    );
+
+    let (s, breakdown) = populate_supply_stats_with_breakdown(&rec).unwrap();
+    assert_eq!(s.normal, 10);
+    assert_eq!(s.slow_total, 100);
+    assert_eq!(s.slow_locked, 60);
+    assert_eq!(s.donor_voice, 50);
+    let categories: Vec<SupplyCategory> = breakdown.iter().map(|a| a.category).collect();
+    assert_eq!(
+        categories,
+        vec![
+            SupplyCategory::Normal,
+            SupplyCategory::Slow,
+            SupplyCategory::DonorDirected
+        ]
+    );
+}
+
+#[test]
+fn infra_escrow_conserves_supply() {
+    let supply = Supply {
+        total: 1_000,
+        slow_total: 800,
+        slow_locked: 600,
+        slow_validator_locked: 500,
+        donor_voice: 100,
+        ..Default::default()
+    };
+    // target 700, less 100 already in donor voice
+    let split = compute_infra_escrow(&supply, 0.7);
+    assert_eq!(split.to_escrow, 500);
+    assert_eq!(split.new_slow_total + split.to_escrow, supply.slow_total);
+    assert_eq!(split.ratio, 1.0);
+    assert_eq!(split.escrow_for(50), 50);
+
+    let split = compute_infra_escrow(&supply, 0.35);
+    assert_eq!(split.to_escrow, 250);
+    assert_eq!(split.ratio, 0.5);
+    assert_eq!(split.escrow_for(51), 25);
+}
+
+#[test]
+fn infra_escrow_without_validator_locked() {
+    let supply = Supply {
+        total: 1_000,
+        slow_total: 300,
+        ..Default::default()
+    };
+    let split = compute_infra_escrow(&supply, 0.7);
+    assert_eq!(split.to_escrow, 0);
+    assert_eq!(split.new_slow_total, 300);
+    assert_eq!(split.ratio, 0.0);
+    assert_eq!(split.escrow_for(100), 0);
 }
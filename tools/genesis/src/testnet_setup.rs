@@ -1,11 +1,18 @@
 use crate::{genesis_builder, parse_json};
 use anyhow::bail;
+use dialoguer::Confirm;
 use diem_genesis::config::{HostAndPort, ValidatorConfiguration};
 use libra_config::validator_config;
 use libra_types::{core_types::fixtures::TestPersona, exports::NamedChain};
-use std::{fs, path::PathBuf, thread, time};
+use std::{
+    fs,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+};
 
 // Sets up the environment for the given test persona.
+// An existing data_path with files in it is only deleted with `force`, or
+// if the user confirms at the terminal.
 pub async fn setup(
     me: &TestPersona,
     host_list: &[HostAndPort],
@@ -13,13 +20,15 @@ pub async fn setup(
     data_path: PathBuf,
     legacy_data_path: Option<PathBuf>,
     framework_mrb_path: Option<PathBuf>,
+    force: bool,
 ) -> anyhow::Result<()> {
     // config the host address for this persona
     if host_list.len() < 3 {
-        bail!("cannot start a testnet with less than 3 nodes, use --host-list for each of Alice, Bob, Carol and Dave but not more. Exiting.")
+        bail!("cannot start a testnet with less than 3 nodes, use --host-list for each of Alice, Bob, Carol, Dave, Eve and Frank but not more. Exiting.")
     }
-    if host_list.len() > 4 {
-        bail!("too many hosts provided, you just need 3 or 4 for a good testnet genesis. Exiting.")
+    let max = TestPersona::ALL.len();
+    if host_list.len() > max {
+        bail!("too many hosts provided, there are only {max} test personas, use 3 to {max} hosts for a testnet genesis. Exiting.")
     }
 
     println!("Building genesis config files for a network with:");
@@ -40,10 +49,20 @@ pub async fn setup(
 
     // create the local files for my_persona
     // let db_path = data_path;
-    if data_path.exists() {
-        println!("WARN: deleting {}, in 5 secs", &data_path.display());
-        let delay = time::Duration::from_secs(5);
-        thread::sleep(delay);
+    // an empty directory, e.g. a mounted volume, can be used as is
+    if data_path.exists() && !is_empty_dir(&data_path)? {
+        if !force {
+            let prompt = format!("{} already exists, delete it?", data_path.display());
+            let confirmed =
+                std::io::stdin().is_terminal() && Confirm::new().with_prompt(prompt).interact()?;
+            if !confirmed {
+                bail!(
+                    "{} already exists, use --force to delete it. Exiting.",
+                    data_path.display()
+                );
+            }
+        }
+        println!("WARN: deleting {}", data_path.display());
         fs::remove_dir_all(&data_path)?;
     }
 
@@ -60,14 +79,23 @@ pub async fn setup(
 
     // create validator configurations from fixtures
     // without needing to use a github repo to register and read
-    let val_cfg: Vec<ValidatorConfiguration> = host_list
-        .iter()
-        .enumerate()
-        .filter_map(|(idx, h)| {
-            let p = TestPersona::from(idx).ok()?;
-            genesis_builder::testnet_validator_config(&p, h).ok()
-        })
-        .collect();
+    // a failed config would silently shrink the validator set, so stop
+    // and report every persona which failed
+    let mut val_cfg: Vec<ValidatorConfiguration> = vec![];
+    let mut failed: Vec<String> = vec![];
+    for (idx, h) in host_list.iter().enumerate() {
+        let p = TestPersona::from(idx)?;
+        match genesis_builder::testnet_validator_config(&p, h) {
+            Ok(cfg) => val_cfg.push(cfg),
+            Err(e) => failed.push(format!("{p} at {}:{}: {e:#}", h.host, h.port)),
+        }
+    }
+    if !failed.is_empty() {
+        bail!(
+            "could not make the validator config for:\n{}",
+            failed.join("\n")
+        );
+    }
 
     // Determines the path for the recovery data.
     // NOTE: test fixtures located at ./tests/fixtures/sample_export_recovery.json
@@ -87,6 +115,23 @@ pub async fn setup(
         &mut recovery,
         chain,
         Some(val_cfg),
+        true,
+        None,
+        false,
     )?;
     Ok(())
 }
+
+fn is_empty_dir(path: &Path) -> anyhow::Result<bool> {
+    Ok(path.is_dir() && fs::read_dir(path)?.next().is_none())
+}
+
+#[test]
+fn only_empty_dirs_are_reused() {
+    let dir = diem_temppath::TempPath::new();
+    dir.create_as_dir().unwrap();
+    assert!(is_empty_dir(dir.path()).unwrap());
+
+    fs::write(dir.path().join("validator.yaml"), "").unwrap();
+    assert!(!is_empty_dir(dir.path()).unwrap());
+}
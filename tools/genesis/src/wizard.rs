@@ -22,7 +22,6 @@ use libra_wallet::keys::VALIDATOR_FILE;
 use std::{
     env, fs,
     path::{Path, PathBuf},
-    thread,
     time::Duration,
 };
 
@@ -126,7 +125,7 @@ impl GenesisWizard {
             // check if the user has the github auth token, and that
             // there is a forked repo on their account.
             // Fork the repo, if it doesn't exist
-            self.git_setup()?;
+            self.git_setup().await?;
 
             self.genesis_registration_github()?;
 
@@ -159,13 +158,16 @@ impl GenesisWizard {
                 &mut legacy_recovery,
                 self.chain,
                 None,
+                true,
+                None,
+                false,
             )?;
 
             for _ in (0..10)
                 .progress_with_style(OLProgress::fun_style())
                 .with_message("Initializing 0L")
             {
-                thread::sleep(Duration::from_millis(100));
+                tokio::time::sleep(Duration::from_millis(100)).await;
             }
         } else {
             println!("Please wait for everyone to finish genesis registration and come back");
@@ -241,7 +243,7 @@ impl GenesisWizard {
     }
 
     /// Sets up the GitHub repository for the genesis process
-    fn git_setup(&mut self) -> anyhow::Result<()> {
+    async fn git_setup(&mut self) -> anyhow::Result<()> {
         let pb = ProgressBar::new(1000).with_style(OLProgress::spinner());
         let gh_client = Client::new(
             self.genesis_repo_org.clone(),
@@ -272,7 +274,7 @@ impl GenesisWizard {
                         Ok(r) => {
                             println!("SUCCESS: repo fork in progress, message: {:?}", r);
                             // give it a few seconds after submitting. Otherwise will get a 500 error while the repo is being created
-                            thread::sleep(Duration::from_secs(5));
+                            tokio::time::sleep(Duration::from_secs(5)).await;
                         }
                         Err(e) => {
                             bail!("Failed to fork repo. We need to fork the genesis repo. Are you sure it's not already forked. {}", e);
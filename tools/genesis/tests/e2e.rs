@@ -9,7 +9,7 @@ use diem_types::{
 use diem_vm_genesis::{TestValidator, Validator};
 use libra_genesis_tools::{
     genesis::{make_recovery_genesis_from_vec_legacy_recovery, save_genesis},
-    parse_json,
+    parse_json, supply,
     vm::libra_genesis_default,
 };
 use std::path::PathBuf;
@@ -155,3 +155,49 @@ fn end_to_end_all() {
 // "c44a50622c2d465e6c15982b8e127349",
 // "3d89b9dabc828735e1215c0714c851f8647f14a85feb267ad78368c10e203322",
 // "647f14a85feb267ad78368c10e203322"]
+
+#[test]
+fn supply_report_sums_to_total() {
+    let temp_dir = TempPath::new();
+    temp_dir.create_as_dir().unwrap();
+
+    let p = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/sample_export_recovery.json");
+
+    let recovery = parse_json::recovery_file_parse(p).unwrap();
+    let s = supply::populate_supply_stats_from_legacy(&recovery).unwrap();
+
+    let escrow = supply::compute_infra_escrow(&s, 0.7);
+    let report = supply::write_supply_report(&s, Some(&escrow), temp_dir.path()).unwrap();
+    assert!(report.exists(), "supply report does not exist");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(report).unwrap()).unwrap();
+    let total = json["total"].as_u64().unwrap();
+    assert!(total > 0);
+    // every account is counted as a slow wallet, a normal account, or a community wallet
+    let parts = json["normal"].as_u64().unwrap()
+        + json["slow_total"].as_u64().unwrap()
+        + json["donor_voice"].as_u64().unwrap();
+    assert_eq!(parts, total);
+    assert!(json["comm_wallets_found"].as_bool().unwrap());
+    assert_eq!(
+        json["escrow"]["to_escrow"].as_u64().unwrap() as u128,
+        escrow.to_escrow
+    );
+}
+
+#[test]
+fn supply_without_comm_wallets() {
+    // this recovery has no 0x0 state and no community wallets
+    let p = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/single.json");
+
+    let recovery = parse_json::recovery_file_parse(p).unwrap();
+    let s = supply::populate_supply_stats_from_legacy(&recovery).unwrap();
+
+    assert!(!s.comm_wallets_found);
+    assert_eq!(s.donor_voice, 0);
+    assert!(s.total > 0);
+    assert_eq!(s.slow_total, s.total);
+    assert_eq!(s.normal, 0);
+}
@@ -0,0 +1,55 @@
+use diem_genesis::config::HostAndPort;
+use diem_temppath::TempPath;
+use libra_framework::release::ReleaseTarget;
+use libra_genesis_tools::testnet_setup;
+use libra_types::{core_types::fixtures::TestPersona, exports::NamedChain};
+use std::time::Instant;
+
+/// two personas set up at once, e.g. by a test harness, run side by side
+/// instead of waiting on each other
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn concurrent_setup_does_not_serialize() {
+    let host_list: Vec<HostAndPort> = ["127.0.0.1:6180", "127.0.0.2:6180", "127.0.0.3:6180"]
+        .iter()
+        .map(|h| h.parse().unwrap())
+        .collect();
+    let mrb = ReleaseTarget::Head
+        .find_bundle_path()
+        .expect("could not find head.mrb, build the framework first");
+
+    let run = |me: TestPersona, dir: &TempPath| {
+        let host_list = host_list.clone();
+        let data_path = dir.path().to_owned();
+        let mrb = mrb.clone();
+        tokio::spawn(async move {
+            let start = Instant::now();
+            testnet_setup::setup(
+                &me,
+                &host_list,
+                NamedChain::TESTING,
+                data_path,
+                None,
+                Some(mrb),
+                true,
+            )
+            .await
+            .map(|_| (start, Instant::now()))
+        })
+    };
+
+    let alice_dir = TempPath::new();
+    let bob_dir = TempPath::new();
+    let (alice, bob) = tokio::join!(
+        run(TestPersona::Alice, &alice_dir),
+        run(TestPersona::Bob, &bob_dir)
+    );
+    let (alice_start, alice_end) = alice.unwrap().expect("alice setup failed");
+    let (bob_start, bob_end) = bob.unwrap().expect("bob setup failed");
+
+    // each started before the other one finished
+    assert!(alice_start < bob_end && bob_start < alice_end);
+
+    for dir in [&alice_dir, &bob_dir] {
+        assert!(dir.path().join("genesis").join("genesis.blob").exists());
+    }
+}
@@ -1,17 +1,33 @@
 //! Helper functions for querying account-related data using the Diem SDK client.
 
+use crate::{
+    chain_queries::get_epoch, query_error::QueryExit, query_retry::retry, query_view::get_view,
+};
+use anyhow::{bail, Context};
 use diem_sdk::{
+    crypto::{bls12381, ValidCryptoMaterialStringExt},
     rest_client::{
-        diem_api_types::{Transaction, VersionedEvent, ViewRequest},
+        diem_api_types::{Transaction, TransactionPayload, VersionedEvent, ViewRequest},
         Client,
     },
-    types::{account_address::AccountAddress, validator_config::ValidatorConfig},
+    types::{
+        account_address::AccountAddress, network_address::NetworkAddress,
+        validator_config::ValidatorConfig,
+    },
 };
+use futures::{future::join_all, TryFutureExt};
 use libra_types::{
-    move_resource::{gas_coin::SlowWalletBalance, txschedule::TxSchedule},
+    move_resource::{
+        ancestry::AncestryResource,
+        gas_coin::{cast_coin_to_decimal, SlowWalletBalance},
+        receipts::ReceiptsResource,
+        txschedule::TxSchedule,
+    },
     type_extensions::client_ext::{entry_function_id, ClientExt},
 };
 use serde_json::{json, Value};
+use std::path::Path;
+use tokio::sync::Semaphore;
 
 /// helper to get libra balance at a SlowWalletBalance type which shows
 /// total balance and the unlocked balance.
@@ -26,17 +42,660 @@ pub async fn get_account_balance_libra(
         arguments: vec![account.to_string().into()],
     };
 
-    let res = client.view(&request, None).await?.into_inner();
+    let res = retry(|| client.view(&request, None).map_err(anyhow::Error::from))
+        .await?
+        .into_inner();
 
     SlowWalletBalance::from_value(res)
 }
 
+/// The balance, and whether the account is a slow wallet. Only a slow wallet
+/// has a locked portion, for any other account the unlocked amount is the total.
+pub async fn get_account_balance_detailed(
+    client: &Client,
+    account: AccountAddress,
+) -> anyhow::Result<(SlowWalletBalance, bool)> {
+    let balance = get_account_balance_libra(client, account).await?;
+    let slow = retry(|| {
+        client
+            .get_account_resource(account, "0x1::slow_wallet::SlowWallet")
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner();
+    Ok((balance, slow.is_some()))
+}
+
+/// The decimal precision of LibraCoin, as reported by the coin module.
+pub async fn get_coin_decimals(client: &Client) -> anyhow::Result<u8> {
+    let res = get_view(
+        client,
+        "0x1::coin::decimals",
+        Some("0x1::libra_coin::LibraCoin".to_string()),
+        None,
+    )
+    .await?;
+    let decimals = res
+        .as_array()
+        .and_then(|a| a.first())
+        .and_then(|v| v.as_u64())
+        .context("unexpected response from coin::decimals")?;
+    Ok(u8::try_from(decimals)?)
+}
+
+/// How many balance requests are in flight at once for `balance --account-file`.
+pub const BALANCE_BATCH_CONCURRENCY: usize = 16;
+/// Default requests in flight for the BalanceBatch query.
+pub const BALANCE_BATCH_DEFAULT_CONCURRENCY: usize = 8;
+
+/// Reads newline separated addresses, skipping blank lines and `#` comments.
+pub fn read_account_file(path: &Path) -> anyhow::Result<Vec<AccountAddress>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("cannot read account file {}", path.display()))?;
+
+    text.lines()
+        .enumerate()
+        .map(|(i, l)| (i, l.trim()))
+        .filter(|(_, l)| !l.is_empty() && !l.starts_with('#'))
+        .map(|(i, l)| {
+            l.parse::<AccountAddress>()
+                .with_context(|| format!("invalid address on line {}: {}", i + 1, l))
+        })
+        .collect()
+}
+
+/// Looks up the balances of many accounts with at most `concurrency`
+/// requests in flight. Each account gets its own result, `None` when the
+/// account doesn't exist, so one bad address doesn't fail the batch.
+pub async fn get_balances_batch(
+    client: &Client,
+    accounts: &[AccountAddress],
+    concurrency: usize,
+) -> Vec<(AccountAddress, anyhow::Result<Option<SlowWalletBalance>>)> {
+    let semaphore = Semaphore::new(concurrency.max(1));
+
+    let requests = accounts.iter().map(|account| {
+        let semaphore = &semaphore;
+        async move {
+            let _permit = semaphore.acquire().await;
+            (*account, balance_if_exists(client, *account).await)
+        }
+    });
+
+    join_all(requests).await
+}
+
+async fn balance_if_exists(
+    client: &Client,
+    account: AccountAddress,
+) -> anyhow::Result<Option<SlowWalletBalance>> {
+    if !account_exists(client, account).await? {
+        return Ok(None);
+    }
+    Ok(Some(get_account_balance_libra(client, account).await?))
+}
+
+/// Checks if an account exists on chain.
+pub async fn account_exists(client: &Client, account: AccountAddress) -> anyhow::Result<bool> {
+    let exists_id = entry_function_id("account", "exists_at")?;
+    let request = ViewRequest {
+        function: exists_id,
+        type_arguments: vec![],
+        arguments: vec![account.to_string().into()],
+    };
+
+    let res = retry(|| client.view(&request, None).map_err(anyhow::Error::from))
+        .await?
+        .into_inner();
+    Ok(serde_json::from_value::<bool>(res[0].clone())?)
+}
+
+/// The unlocked and locked split of a slow wallet, in coins.
+/// All the fields are null when the account is not a slow wallet.
+pub async fn get_slow_wallet(client: &Client, account: AccountAddress) -> anyhow::Result<Value> {
+    let addr = Some(account.to_hex_literal());
+    let is_slow = view_first_value(client, "0x1::slow_wallet::is_slow", addr.clone()).await?;
+    if is_slow != Value::Bool(true) {
+        return Ok(json!({
+          "unlocked": null,
+          "locked": null,
+          "transferred": null,
+          "total": null,
+        }));
+    }
+
+    let unlocked = view_u64(client, "0x1::slow_wallet::unlocked_amount", addr.clone()).await?;
+    let transferred = view_u64(client, "0x1::slow_wallet::transferred_amount", addr).await?;
+    let total = get_account_balance_libra(client, account).await?.total;
+
+    Ok(json!({
+      "unlocked": cast_coin_to_decimal(unlocked),
+      "locked": cast_coin_to_decimal(total.saturating_sub(unlocked)),
+      "transferred": cast_coin_to_decimal(transferred),
+      "total": cast_coin_to_decimal(total),
+    }))
+}
+
+/// Vouches received by and given by an account, with the epoch of each vouch.
+pub async fn get_vouches(client: &Client, account: AccountAddress) -> anyhow::Result<Value> {
+    let received = get_view(
+        client,
+        "0x1::vouch::get_received_vouches",
+        None,
+        Some(account.to_hex_literal()),
+    )
+    .await?;
+    let given = get_given_vouches(client, account).await?;
+
+    Ok(json!({
+      "received": zip_vouches(&received, "giver")?,
+      "given": zip_vouches(&given, "receiver")?,
+    }))
+}
+
+/// The most vouches an account may give, BASE_MAX_VOUCHES in vouch.move.
+pub const VOUCH_GIVEN_LIMIT: u64 = 10;
+
+/// The accounts vouching for an account and how many vouches each has
+/// given, and the account's own given vouches against the limit.
+pub async fn get_vouch_limits(client: &Client, account: AccountAddress) -> anyhow::Result<Value> {
+    let received = get_view(
+        client,
+        "0x1::vouch::get_received_vouches",
+        None,
+        Some(account.to_hex_literal()),
+    )
+    .await?;
+    let (vouchers, _): (Vec<String>, Vec<String>) = serde_json::from_value(received)
+        .context("unexpected response from get_received_vouches")?;
+
+    let mut received_from = vec![];
+    for v in &vouchers {
+        let voucher = AccountAddress::from_hex_literal(v)?;
+        received_from.push(json!({
+          "address": v,
+          "given_count": given_vouch_count(client, voucher).await?,
+        }));
+    }
+
+    Ok(json!({
+      "received_from": received_from,
+      "received_count": vouchers.len(),
+      "given_count": given_vouch_count(client, account).await?,
+      "limit": VOUCH_GIVEN_LIMIT,
+    }))
+}
+
+/// The get_given_vouches view, or empty lists if the account never
+/// initialized its given vouches, since the view aborts in that case.
+async fn get_given_vouches(client: &Client, account: AccountAddress) -> anyhow::Result<Value> {
+    let has_given = retry(|| {
+        client
+            .get_account_resource(account, "0x1::vouch::GivenVouches")
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner()
+    .is_some();
+    if !has_given {
+        return Ok(json!([[], []]));
+    }
+    get_view(
+        client,
+        "0x1::vouch::get_given_vouches",
+        None,
+        Some(account.to_hex_literal()),
+    )
+    .await
+}
+
+async fn given_vouch_count(client: &Client, account: AccountAddress) -> anyhow::Result<usize> {
+    let given = get_given_vouches(client, account).await?;
+    Ok(given
+        .get(0)
+        .and_then(|v| v.as_array())
+        .map(|a| a.len())
+        .unwrap_or_default())
+}
+
+/// pairs the (vector<address>, vector<u64>) returned by the vouch views
+fn zip_vouches(res: &Value, key: &str) -> anyhow::Result<Vec<Value>> {
+    let (addrs, epochs): (Vec<String>, Vec<String>) =
+        serde_json::from_value(res.clone()).context("unexpected response from the vouch view")?;
+
+    addrs
+        .into_iter()
+        .zip(epochs)
+        .map(|(a, e)| Ok(json!({ key: a, "epoch": e.parse::<u64>()? })))
+        .collect()
+}
+
+/// A validator's proof-of-fee bid for the upcoming epoch's auction.
+/// The chain reports a zero bid once it has expired.
+pub async fn get_pof_bid(client: &Client, account: AccountAddress) -> anyhow::Result<Value> {
+    let res = get_view(
+        client,
+        "0x1::proof_of_fee::current_bid",
+        None,
+        Some(account.to_hex_literal()),
+    )
+    .await?;
+    let (bid, expiration): (String, String) =
+        serde_json::from_value(res).context("unexpected response from current_bid")?;
+    let bid: u64 = bid.parse()?;
+    let expiration: u64 = expiration.parse()?;
+    let epoch = get_epoch(client).await?;
+
+    Ok(json!({
+      "bid": bid,
+      "expiration_epoch": expiration,
+      "is_valid": bid > 0,
+      // a bid counts in the auction until the end of its expiration epoch
+      "is_active": bid > 0 && expiration >= epoch,
+    }))
+}
+
+/// first return value of a view function
+async fn view_first_value(
+    client: &Client,
+    function_id: &str,
+    args: Option<String>,
+) -> anyhow::Result<Value> {
+    let res = get_view(client, function_id, None, args).await?;
+    res.as_array()
+        .and_then(|a| a.first())
+        .cloned()
+        .with_context(|| format!("no value returned from {function_id}"))
+}
+
+/// first return value of a view function, parsed from the api's u64 string
+async fn view_u64(client: &Client, function_id: &str, args: Option<String>) -> anyhow::Result<u64> {
+    let v = view_first_value(client, function_id, args).await?;
+    v.as_str()
+        .with_context(|| format!("unexpected value from {function_id}: {v}"))?
+        .parse::<u64>()
+        .with_context(|| format!("unexpected value from {function_id}: {v}"))
+}
+
+/// Is there a payment with this id still scheduled (and so can be vetoed)
+/// at a donor voice account.
+pub async fn is_donor_voice_tx_scheduled(
+    client: &Client,
+    community_wallet: AccountAddress,
+    id: u64,
+) -> anyhow::Result<bool> {
+    let scheduled = get_resource_field(
+        client,
+        community_wallet,
+        "0x1::donor_voice_txs::TxSchedule",
+        "scheduled",
+    )
+    .await?;
+
+    let id = id.to_string();
+    Ok(scheduled
+        .as_array()
+        .map(|list| {
+            list.iter()
+                .any(|t| t["uid"]["creation_num"].as_str() == Some(id.as_str()))
+        })
+        .unwrap_or(false))
+}
+
+/// The veto tally of a scheduled payment: the approval of the veto so far,
+/// and the approval required for it to pass.
+pub async fn get_veto_tally(
+    client: &Client,
+    community_wallet: AccountAddress,
+    id: u64,
+) -> anyhow::Result<Value> {
+    let res = get_view(
+        client,
+        "0x1::donor_voice_governance::get_veto_tally",
+        None,
+        Some(format!("{},{}", community_wallet.to_hex_literal(), id)),
+    )
+    .await?;
+    Ok(json!({
+      "proposal_id": id,
+      "approval": parse_u64_field(&res[0])?,
+      "threshold": parse_u64_field(&res[1])?,
+    }))
+}
+
+/// Is a liquidation of the Donor Voice wallet pending a vote
+pub async fn is_liquidation_proposed(
+    client: &Client,
+    community_wallet: AccountAddress,
+) -> anyhow::Result<bool> {
+    // sic, the view is misspelled on chain
+    let v = view_first_value(
+        client,
+        "0x1::donor_voice_governance::is_liquidation_propsed",
+        Some(community_wallet.to_hex_literal()),
+    )
+    .await?;
+    v.as_bool()
+        .with_context(|| format!("unexpected value from is_liquidation_propsed: {v}"))
+}
+
+/// Is the Donor Voice wallet frozen, e.g. once donors voted to liquidate it
+pub async fn is_donor_voice_frozen(
+    client: &Client,
+    community_wallet: AccountAddress,
+) -> anyhow::Result<bool> {
+    let frozen = get_resource_field(
+        client,
+        community_wallet,
+        "0x1::donor_voice_txs::Freeze",
+        "is_frozen",
+    )
+    .await?;
+    frozen
+        .as_bool()
+        .with_context(|| format!("unexpected value of is_frozen: {frozen}"))
+}
+
+/// Lists the type of every resource held at an account.
+pub async fn get_account_resource_types(
+    client: &Client,
+    account: AccountAddress,
+) -> anyhow::Result<Vec<String>> {
+    let res = retry(|| {
+        client
+            .get_account_resources(account)
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner();
+    Ok(res.iter().map(|r| r.resource_type.to_string()).collect())
+}
+
+/// Every resource of an account, as a map of resource type to its data.
+pub async fn get_all_resources(client: &Client, account: AccountAddress) -> anyhow::Result<Value> {
+    let res = retry(|| {
+        client
+            .get_account_resources(account)
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner();
+    let map: serde_json::Map<String, Value> = res
+        .into_iter()
+        .map(|r| (r.resource_type.to_string(), r.data))
+        .collect();
+    Ok(Value::Object(map))
+}
+
+/// Reads a single field out of an account resource, such as the `unlocked`
+/// field of `0x1::slow_wallet::SlowWallet`.
+pub async fn get_resource_field(
+    client: &Client,
+    account: AccountAddress,
+    resource_path: &str,
+    key: &str,
+) -> anyhow::Result<Value> {
+    let res = retry(|| {
+        client
+            .get_account_resource(account, resource_path)
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner()
+    .with_context(|| {
+        format!("resource_not_found: no resource {resource_path} at address {account}")
+    })?;
+
+    match res.data.get(key) {
+        Some(v) => Ok(v.clone()),
+        None => {
+            let available: Vec<&str> = res
+                .data
+                .as_object()
+                .map(|o| o.keys().map(|k| k.as_str()).collect())
+                .unwrap_or_default();
+            bail!(
+                "key_not_found: no key {key} in {resource_path}, available keys: {}",
+                available.join(", ")
+            )
+        }
+    }
+}
+
+/// The ancestors of an account, ordered from the root to the parent.
+pub async fn get_ancestry(client: &Client, account: AccountAddress) -> anyhow::Result<Value> {
+    let res = retry(|| client.get_move_resource::<AncestryResource>(account)).await?;
+    let tree: Vec<String> = res.tree.iter().map(|a| a.to_hex_literal()).collect();
+    Ok(json!({ "tree": tree }))
+}
+
+/// Pledges an account has made, with the beneficiary's revocation policy.
+/// An account which never pledged has an empty list.
+pub async fn get_pledges(client: &Client, account: AccountAddress) -> anyhow::Result<Value> {
+    let res = retry(|| {
+        client
+            .get_account_resource(account, "0x1::pledge_accounts::MyPledges")
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner();
+    let list = match res {
+        Some(r) => r.data["list"]
+            .as_array()
+            .cloned()
+            .context("unexpected MyPledges resource")?,
+        None => return Ok(json!([])),
+    };
+
+    let mut pledges = vec![];
+    for p in list {
+        let beneficiary = p["address_of_beneficiary"]
+            .as_str()
+            .context("unexpected PledgeAccount resource")?;
+        let beneficiary = AccountAddress::from_hex_literal(beneficiary)?;
+        let policy = retry(|| {
+            client
+                .get_account_resource(beneficiary, "0x1::pledge_accounts::BeneficiaryPolicy")
+                .map_err(anyhow::Error::from)
+        })
+        .await?
+        .into_inner();
+
+        // a pledge can be revoked by a vote of the pledgers, until it has been
+        let (revocable, threshold) = match policy {
+            Some(r) => (
+                r.data["revoked"].as_bool() != Some(true),
+                Some(parse_u64_field(&r.data["vote_threshold_to_revoke"])?),
+            ),
+            None => (false, None),
+        };
+
+        pledges.push(json!({
+          "beneficiary": beneficiary.to_hex_literal(),
+          "amount": parse_u64_field(&p["amount"])?,
+          "lifetime_pledged": parse_u64_field(&p["lifetime_pledged"])?,
+          "lifetime_withdrawn": parse_u64_field(&p["lifetime_withdrawn"])?,
+          "revocable": revocable,
+          "vote_threshold_to_revoke": threshold,
+        }));
+    }
+    Ok(json!(pledges))
+}
+
+/// u64 fields of a json resource are strings
+fn parse_u64_field(v: &Value) -> anyhow::Result<u64> {
+    v.as_str()
+        .with_context(|| format!("expected a u64 string, got {v}"))?
+        .parse::<u64>()
+        .with_context(|| format!("expected a u64 string, got {v}"))
+}
+
+/// Donations an account has made, one receipt per destination. An account
+/// which never donated has no receipts.
+pub async fn get_receipts(client: &Client, account: AccountAddress) -> anyhow::Result<Value> {
+    let res = retry(|| {
+        client
+            .get_account_resource_bcs::<ReceiptsResource>(account, "0x1::receipts::UserReceipts")
+            .map_err(anyhow::Error::from)
+    })
+    .await;
+    // the resource is only created with the first donation
+    let r = match res {
+        Ok(r) => r.into_inner(),
+        Err(e) if QueryExit::from_error(&e) == QueryExit::NotFound => return Ok(json!([])),
+        Err(e) => return Err(e),
+    };
+
+    let receipts: Vec<Value> = r
+        .destination
+        .iter()
+        .zip(&r.cumulative)
+        .zip(&r.last_payment_timestamp)
+        .zip(&r.last_payment_value)
+        .map(|(((to, cumulative), timestamp), value)| {
+            json!({
+              "to": to.to_hex_literal(),
+              "cumulative": cumulative,
+              "last_payment_timestamp": timestamp,
+              "last_payment_value": value,
+            })
+        })
+        .collect();
+    Ok(json!(receipts))
+}
+
+/// Fees an account has paid in this epoch and over its lifetime. Zero for
+/// an account which never paid a fee.
+pub async fn get_fee_maker(client: &Client, account: AccountAddress) -> anyhow::Result<Value> {
+    let res = retry(|| {
+        client
+            .get_account_resource(account, "0x1::fee_maker::FeeMaker")
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner();
+    let (epoch, lifetime) = match res {
+        Some(r) => (
+            parse_u64_field(&r.data["epoch"])?,
+            parse_u64_field(&r.data["lifetime"])?,
+        ),
+        None => (0, 0),
+    };
+    Ok(json!({
+      "epoch_fees_made": epoch,
+      "lifetime_fees_made": lifetime,
+    }))
+}
+
+/// Fees made by all accounts in this epoch, and how many accounts made them.
+pub async fn get_all_fees_made(client: &Client) -> anyhow::Result<Value> {
+    let epoch_fees = view_u64(client, "0x1::fee_maker::get_all_fees_made", None).await?;
+    let res = get_view(client, "0x1::fee_maker::get_fee_makers", None, None).await?;
+    let (fee_makers,): (Vec<String>,) =
+        serde_json::from_value(res).context("unexpected response from get_fee_makers")?;
+    Ok(json!({
+      "epoch_fees_made": epoch_fees,
+      "fee_makers": fee_makers.len(),
+    }))
+}
+
+/// Network addresses as multiaddr strings, e.g.
+/// `/ip4/1.2.3.4/tcp/6180/noise-ik/0x<key>/handshake/0`, each with the
+/// noise public key peers use to authenticate it.
+pub fn render_network_addresses(addrs: &[NetworkAddress]) -> anyhow::Result<Value> {
+    let list = addrs
+        .iter()
+        .map(|a| {
+            let key = a
+                .find_noise_proto()
+                .map(|k| k.to_encoded_string())
+                .transpose()?;
+            Ok(json!({
+              "address": a.to_string(),
+              "noise_public_key": key,
+            }))
+        })
+        .collect::<anyhow::Result<Vec<Value>>>()?;
+    Ok(json!(list))
+}
+
 /// Retrieves the validator configuration for a given account.
 pub async fn get_val_config(
     client: &Client,
     account: AccountAddress,
 ) -> anyhow::Result<ValidatorConfig> {
-    client.get_move_resource::<ValidatorConfig>(account).await
+    retry(|| client.get_move_resource::<ValidatorConfig>(account)).await
+}
+
+/// The validator config as json, with each field decoded on its own. A field
+/// which can't be decoded, e.g. on a partially configured validator, is
+/// shown as `{"raw_hex": ..}` so the rest of the config is still readable.
+/// With `strict` any field which can't be decoded is an error instead.
+pub async fn get_val_config_json(
+    client: &Client,
+    account: AccountAddress,
+    strict: bool,
+) -> anyhow::Result<Value> {
+    let res = retry(|| {
+        client
+            .get_account_resource(account, "0x1::stake::ValidatorConfig")
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner()
+    .with_context(|| format!("resource_not_found: no validator config at address {account}"))?;
+
+    render_val_config(&res.data, strict)
+}
+
+/// Decode the fields of a `0x1::stake::ValidatorConfig` resource.
+pub fn render_val_config(data: &Value, strict: bool) -> anyhow::Result<Value> {
+    let field_bytes = |name: &str| -> anyhow::Result<Vec<u8>> {
+        let s = data[name]
+            .as_str()
+            .with_context(|| format!("no {name} in the validator config"))?;
+        Ok(hex::decode(s.trim_start_matches("0x"))?)
+    };
+
+    let consensus_public_key = decode_or_raw(
+        &field_bytes("consensus_pubkey")?,
+        strict,
+        "consensus public key",
+        |b| Ok(json!(bls12381::PublicKey::try_from(b)?.to_encoded_string()?)),
+    )?;
+    let validator_network_addresses = decode_or_raw(
+        &field_bytes("network_addresses")?,
+        strict,
+        "validator network address",
+        |b| render_network_addresses(&diem_sdk::bcs::from_bytes::<Vec<NetworkAddress>>(b)?),
+    )?;
+    let fullnode_network_addresses = decode_or_raw(
+        &field_bytes("fullnode_addresses")?,
+        strict,
+        "fullnode network address",
+        |b| render_network_addresses(&diem_sdk::bcs::from_bytes::<Vec<NetworkAddress>>(b)?),
+    )?;
+
+    Ok(json!({
+      "consensus_public_key": consensus_public_key,
+      "validator_network_addresses": validator_network_addresses,
+      "fullnode_network_addresses": fullnode_network_addresses,
+      "validator_index": parse_u64_field(&data["validator_index"])?,
+    }))
+}
+
+/// the decoded field, or its bytes as `{"raw_hex": ..}` if it can't be decoded
+fn decode_or_raw(
+    bytes: &[u8],
+    strict: bool,
+    field: &str,
+    decode: impl Fn(&[u8]) -> anyhow::Result<Value>,
+) -> anyhow::Result<Value> {
+    match decode(bytes) {
+        Ok(v) => Ok(v),
+        Err(e) if strict => Err(e.context(format!("can't decode the {field}"))),
+        Err(_) => Ok(json!({ "raw_hex": hex::encode(bytes) })),
+    }
 }
 
 /// Retrieves events associated with a given account.
@@ -51,33 +710,139 @@ pub async fn get_events(
     } else {
         "deposit_events"
     };
-    let res = client
-        .get_account_events(
-            account,
-            "0x1::coin::CoinStore<0x1::libra_coin::LibraCoin>",
-            direction,
-            seq_start,
-            None,
-        )
+    let res = retry(|| {
+        client
+            .get_account_events(
+                account,
+                "0x1::coin::CoinStore<0x1::libra_coin::LibraCoin>",
+                direction,
+                seq_start,
+                None,
+            )
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner();
+    Ok(res)
+}
+
+/// Every key rotation of an account, oldest first. Each entry has the
+/// authentication key which took over at that version, and the key it replaced.
+pub async fn get_rotation_history(
+    client: &Client,
+    account: AccountAddress,
+) -> anyhow::Result<Vec<Value>> {
+    let page_size: u16 = 100;
+    let mut start = 0;
+    let mut events: Vec<VersionedEvent> = vec![];
+    loop {
+        let page = retry(|| {
+            client
+                .get_account_events(
+                    account,
+                    "0x1::account::Account",
+                    "key_rotation_events",
+                    Some(start),
+                    Some(page_size),
+                )
+                .map_err(anyhow::Error::from)
+        })
         .await?
         .into_inner();
-    Ok(res)
+        let fetched = page.len() as u64;
+        events.extend(page);
+        if fetched < page_size as u64 {
+            break;
+        }
+        start += fetched;
+    }
+
+    let mut history = vec![];
+    for e in events {
+        let version = u64::from(e.version);
+        let tx = retry(|| {
+            client
+                .get_transaction_by_version(version)
+                .map_err(anyhow::Error::from)
+        })
+        .await?
+        .into_inner();
+        history.push(json!({
+          "auth_key": e.data["new_authentication_key"],
+          "previous_auth_key": e.data["old_authentication_key"],
+          "version": version,
+          "timestamp": tx.timestamp(),
+        }));
+    }
+    Ok(history)
 }
 
-/// Retrieves transactions associated with a given account.
+/// The page size of the account transactions endpoint.
+pub const TXS_PAGE_SIZE: u64 = 100;
+/// Most transactions returned by one history query, so we don't hammer the node.
+pub const TXS_MAX_COUNT: u64 = 1000;
+/// Most pages read by one history query, matching the filter or not.
+pub const TXS_MAX_PAGES: u64 = 50;
+
+/// Retrieves transactions associated with a given account, starting at the
+/// account sequence number `txs_height`. Pages through the history until
+/// `txs_count` transactions (default 100, at most 1000) are found or the
+/// history ends. When `txs_type` is set only transactions whose entry
+/// function contains it are kept, e.g. `ol_account::transfer`.
+/// A filter which rarely matches stops after `TXS_MAX_PAGES` pages; the
+/// sequence number to resume from is then returned with the partial result.
 pub async fn get_transactions(
     client: &Client,
     account: AccountAddress,
     txs_height: Option<u64>,
     txs_count: Option<u64>,
-    _txs_type: Option<String>,
-) -> anyhow::Result<Vec<Transaction>> {
-    // TODO: filter by type (what type is it?)
-    let res = client
-        .get_account_transactions(account, txs_height, txs_count)
+    txs_type: Option<String>,
+) -> anyhow::Result<(Vec<Transaction>, Option<u64>)> {
+    let count = txs_count.unwrap_or(TXS_PAGE_SIZE).min(TXS_MAX_COUNT) as usize;
+    let mut start = txs_height.unwrap_or(0);
+    let mut res: Vec<Transaction> = vec![];
+    let mut pages = 0;
+
+    while res.len() < count {
+        if pages == TXS_MAX_PAGES {
+            return Ok((res, Some(start)));
+        }
+        pages += 1;
+
+        let page = retry(|| {
+            client
+                .get_account_transactions(account, Some(start), Some(TXS_PAGE_SIZE))
+                .map_err(anyhow::Error::from)
+        })
         .await?
         .into_inner();
-    Ok(res)
+        let fetched = page.len() as u64;
+
+        res.extend(page.into_iter().filter(|tx| match &txs_type {
+            Some(t) => tx_entry_function(tx).map_or(false, |f| f.contains(t.as_str())),
+            None => true,
+        }));
+
+        // the end of the account's history
+        if fetched < TXS_PAGE_SIZE {
+            break;
+        }
+        start += fetched;
+    }
+
+    res.truncate(count);
+    Ok((res, None))
+}
+
+/// The entry function a user transaction called, e.g. `0x1::ol_account::transfer`
+pub fn tx_entry_function(tx: &Transaction) -> Option<String> {
+    match tx {
+        Transaction::UserTransaction(u) => match &u.request.payload {
+            TransactionPayload::EntryFunctionPayload(p) => Some(p.function.to_string()),
+            _ => None,
+        },
+        _ => None,
+    }
 }
 
 /// Checks if the community wallet for a given account has been migrated.
@@ -92,7 +857,9 @@ pub async fn is_community_wallet_migrated(
         arguments: vec![account.to_string().into()],
     };
 
-    let res = client.view(&request, None).await?.into_inner();
+    let res = retry(|| client.view(&request, None).map_err(anyhow::Error::from))
+        .await?
+        .into_inner();
     Ok(json!(res))
 }
 
@@ -109,7 +876,9 @@ pub async fn community_wallet_signers(
         arguments: vec![account.to_string().into()],
     };
 
-    let res = client.view(&request, None).await?.into_inner();
+    let res = retry(|| client.view(&request, None).map_err(anyhow::Error::from))
+        .await?
+        .into_inner();
     Ok(json!(res))
 }
 
@@ -118,7 +887,23 @@ pub async fn community_wallet_scheduled_transactions(
     client: &Client,
     account: AccountAddress,
 ) -> anyhow::Result<TxSchedule> {
-    client.get_move_resource::<TxSchedule>(account).await
+    retry(|| client.get_move_resource::<TxSchedule>(account)).await
+}
+
+/// The id the next multi_auth proposal on this account will get. Proposal ids
+/// are taken from the account's guid counter.
+pub async fn get_next_multi_auth_proposal_id(
+    client: &Client,
+    multi_auth_account: AccountAddress,
+) -> anyhow::Result<u64> {
+    let v = get_resource_field(
+        client,
+        multi_auth_account,
+        "0x1::account::Account",
+        "guid_creation_num",
+    )
+    .await?;
+    parse_u64_field(&v)
 }
 
 /// Retrieves all multi_auth actions (pending, approved, expired) for a given multi_auth account.
@@ -127,10 +912,68 @@ pub async fn multi_auth_ballots(
     multi_auth_account: AccountAddress,
 ) -> anyhow::Result<Value> {
     let resource_path_str = "0x1::multi_action::Action<0x1::donor_voice_txs::Payment>";
-    let proposal_state = client
-        .get_account_resource(multi_auth_account, resource_path_str)
-        .await?;
+    let proposal_state = retry(|| {
+        client
+            .get_account_resource(multi_auth_account, resource_path_str)
+            .map_err(anyhow::Error::from)
+    })
+    .await?;
     let r = proposal_state.inner().clone().unwrap();
 
     Ok(r.data)
 }
+
+#[test]
+fn render_network_address_multiaddr() {
+    let key = "0x080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120";
+    let multiaddr = format!("/ip4/10.0.0.1/tcp/6180/noise-ik/{key}/handshake/0");
+    let addr: NetworkAddress = multiaddr.parse().unwrap();
+
+    // as stored on chain in the validator config
+    let bytes = diem_sdk::bcs::to_bytes(&vec![addr]).unwrap();
+    let decoded: Vec<NetworkAddress> = diem_sdk::bcs::from_bytes(&bytes).unwrap();
+
+    let v = render_network_addresses(&decoded).unwrap();
+    assert_eq!(v[0]["address"], multiaddr);
+    assert_eq!(v[0]["noise_public_key"], key);
+}
+
+#[test]
+fn render_val_config_raw_hex() {
+    let data = json!({
+      "consensus_pubkey": "0x0102",
+      "network_addresses": "0xff",
+      "fullnode_addresses": "0x00",
+      "validator_index": "4",
+    });
+
+    // the malformed fields are kept as hex, the rest is still decoded
+    let v = render_val_config(&data, false).unwrap();
+    assert_eq!(v["consensus_public_key"]["raw_hex"], "0102");
+    assert_eq!(v["validator_network_addresses"]["raw_hex"], "ff");
+    assert_eq!(v["fullnode_network_addresses"], json!([]));
+    assert_eq!(v["validator_index"], 4);
+
+    let err = render_val_config(&data, true).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("can't decode the consensus public key"));
+}
+
+#[test]
+fn vouch_given_limit_matches_framework() {
+    // there is no view for the limit, so keep it in step with the source
+    let vouch_move = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../framework/libra-framework/sources/ol_sources/vouch.move");
+    let src = std::fs::read_to_string(vouch_move).unwrap();
+    let line = src
+        .lines()
+        .find(|l| l.trim_start().starts_with("const BASE_MAX_VOUCHES"))
+        .expect("BASE_MAX_VOUCHES not found in vouch.move");
+    let value = line
+        .split('=')
+        .nth(1)
+        .and_then(|v| v.trim().trim_end_matches(';').parse::<u64>().ok())
+        .unwrap();
+    assert_eq!(value, VOUCH_GIVEN_LIMIT);
+}
@@ -1,8 +1,20 @@
 //! chain queries
 
-use crate::query_view::{self, get_view};
-use anyhow::Context;
-use diem_sdk::rest_client::Client;
+use crate::{
+    query_retry::retry,
+    query_view::{self, get_view},
+};
+use anyhow::{bail, Context};
+use diem_sdk::{
+    crypto::HashValue,
+    rest_client::Client,
+    types::{
+        account_address::AccountAddress, block_info::BlockInfo, epoch_state::EpochState,
+        ledger_info::LedgerInfo, on_chain_config::ValidatorSet, waypoint::Waypoint,
+    },
+};
+use futures::{future::join_all, stream, StreamExt, TryFutureExt, TryStreamExt};
+use url::Url;
 
 /// Retrieves the current epoch from the blockchain.
 pub async fn get_epoch(client: &Client) -> anyhow::Result<u64> {
@@ -20,6 +32,111 @@ pub async fn get_epoch(client: &Client) -> anyhow::Result<u64> {
     Ok(num)
 }
 
+/// The waypoint of the last epoch boundary, the same a node syncing from the
+/// start of the current epoch would trust.
+/// It is rebuilt from the ledger info which ended the previous epoch: the
+/// last version of the block with the reconfiguration event, its accumulator
+/// root hash and timestamp, and the validator set at that version.
+pub async fn get_epoch_waypoint(client: &Client) -> anyhow::Result<Waypoint> {
+    // without a start the node returns the most recent event
+    let new_epoch = retry(|| {
+        client
+            .get_account_events(
+                AccountAddress::ONE,
+                "0x1::reconfiguration::Configuration",
+                "events",
+                None,
+                Some(1),
+            )
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner()
+    .pop()
+    .context("no reconfiguration events found")?;
+
+    let epoch: u64 = new_epoch.data["epoch"]
+        .as_str()
+        .context("unexpected NewEpochEvent")?
+        .parse()?;
+
+    let block = retry(|| {
+        client
+            .get_block_by_version(new_epoch.version.into(), false)
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner();
+    let version: u64 = block.last_version.into();
+
+    let tx = retry(|| {
+        client
+            .get_transaction_by_version(version)
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner();
+    let root_hash: HashValue = tx.transaction_info()?.accumulator_root_hash.into();
+
+    let validator_set = retry(|| {
+        client
+            .get_account_resource_at_version_bcs::<ValidatorSet>(
+                AccountAddress::ONE,
+                "0x1::stake::ValidatorSet",
+                version,
+            )
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner();
+
+    let next_epoch_state = EpochState {
+        epoch,
+        verifier: (&validator_set).into(),
+    };
+    let block_info = BlockInfo::new(
+        epoch.saturating_sub(1),
+        0,
+        HashValue::zero(),
+        root_hash,
+        version,
+        block.block_timestamp.into(),
+        Some(next_epoch_state),
+    );
+    Waypoint::new_epoch_boundary(&LedgerInfo::new(block_info, HashValue::zero()))
+}
+
+/// Estimated seconds until the epoch can end, from the epoch interval and
+/// the time of the last reconfiguration. Zero if the epoch is overdue.
+pub async fn get_seconds_to_next_epoch(client: &Client) -> anyhow::Result<u64> {
+    let res = get_view(client, "0x1::block::get_epoch_interval_secs", None, None).await?;
+    let interval_secs = serde_json::from_value::<Vec<String>>(res)?
+        .first()
+        .context("could not get a response from view function get_epoch_interval_secs")?
+        .parse::<u64>()?;
+
+    let config = retry(|| {
+        client
+            .get_account_resource(AccountAddress::ONE, "0x1::reconfiguration::Configuration")
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner()
+    .context("no reconfiguration::Configuration found")?;
+    let last_reconfig_usecs: u64 = config.data["last_reconfiguration_time"]
+        .as_str()
+        .context("unexpected reconfiguration::Configuration")?
+        .parse()?;
+
+    let now_usecs = retry(|| client.get_ledger_information().map_err(anyhow::Error::from))
+        .await?
+        .into_inner()
+        .timestamp_usecs;
+
+    let next_epoch_secs = last_reconfig_usecs / 1_000_000 + interval_secs;
+    Ok(next_epoch_secs.saturating_sub(now_usecs / 1_000_000))
+}
+
 // COMMIT NOTE: deprecated tower functions
 
 /// Retrieves the ID of the next governance proposal.
@@ -93,6 +210,254 @@ pub async fn get_height(client: &Client) -> anyhow::Result<u64> {
     Ok(height)
 }
 
+/// Retrieves the block height and ledger version from the node's latest ledger info.
+pub async fn get_ledger_height(client: &Client) -> anyhow::Result<(u64, u64)> {
+    let state = retry(|| client.get_ledger_information().map_err(anyhow::Error::from))
+        .await?
+        .into_inner();
+    if state.block_height == 0 {
+        bail!(
+            "node has no blocks yet at version {}, it may still be syncing",
+            state.version
+        );
+    }
+
+    Ok((state.block_height, state.version))
+}
+
+/// How far the local node is behind the upstream nodes.
+#[derive(Debug, Clone)]
+pub struct SyncDelay {
+    /// ledger version of the local node
+    pub local_version: u64,
+    /// highest ledger version reported by the upstream nodes
+    pub upstream_version: u64,
+    /// versions the local node is behind, zero if it is ahead
+    pub version_behind: u64,
+    /// seconds between the ledger timestamps, zero if the local node is ahead
+    pub seconds_behind: u64,
+    /// upstream nodes which could not be reached
+    pub unreachable: Vec<Url>,
+}
+
+/// Compares the local node's ledger info against the most advanced of the
+/// upstream nodes. Upstream nodes which cannot be reached are skipped.
+pub async fn get_sync_delay(client: &Client, upstream: &[Url]) -> anyhow::Result<SyncDelay> {
+    let local = retry(|| client.get_ledger_information().map_err(anyhow::Error::from))
+        .await
+        .context("cannot get ledger info from the local node")?
+        .into_inner();
+
+    let requests = upstream.iter().map(|u| async move {
+        let res = Client::new(u.to_owned()).get_ledger_information().await;
+        (u.to_owned(), res)
+    });
+
+    let mut unreachable = vec![];
+    let mut highest: Option<(u64, u64)> = None;
+    for (url, res) in join_all(requests).await {
+        match res {
+            Ok(r) => {
+                let state = r.into_inner();
+                if highest.map_or(true, |(v, _)| state.version > v) {
+                    highest = Some((state.version, state.timestamp_usecs));
+                }
+            }
+            Err(_) => unreachable.push(url),
+        }
+    }
+
+    let (upstream_version, upstream_usecs) =
+        highest.context("none of the upstream nodes could be reached")?;
+
+    Ok(SyncDelay {
+        local_version: local.version,
+        upstream_version,
+        version_behind: upstream_version.saturating_sub(local.version),
+        seconds_behind: upstream_usecs.saturating_sub(local.timestamp_usecs) / 1_000_000,
+        unreachable,
+    })
+}
+
+/// The proof-of-fee auction: the clearing price of the last auction, and the
+/// seats offered for the upcoming epoch.
+pub async fn get_pof_auction(client: &Client) -> anyhow::Result<serde_json::Value> {
+    let res = get_view(
+        client,
+        "0x1::proof_of_fee::get_consensus_reward",
+        None,
+        None,
+    )
+    .await?;
+    let (reward, entry_fee, clearing_bid, median_win_bid): (String, String, String, String) =
+        serde_json::from_value(res).context("unexpected response from get_consensus_reward")?;
+
+    let seats = get_seats_offered(client).await?;
+
+    Ok(serde_json::json!({
+      "clearing_bid": clearing_bid.parse::<u64>()?,
+      "entry_fee": entry_fee.parse::<u64>()?,
+      "median_win_bid": median_win_bid.parse::<u64>()?,
+      "nominal_reward": reward.parse::<u64>()?,
+      "seats_offered": seats,
+    }))
+}
+
+/// Qualified bidders sorted by bid as the auction ranks them, and whether
+/// each would win one of the seats offered for the next epoch.
+pub async fn get_pof_bidders(client: &Client) -> anyhow::Result<serde_json::Value> {
+    let res = get_view(
+        client,
+        "0x1::proof_of_fee::get_bidders_and_bids",
+        None,
+        Some("true".to_string()),
+    )
+    .await?;
+    let (bidders, bids): (Vec<String>, Vec<String>) =
+        serde_json::from_value(res).context("unexpected response from get_bidders_and_bids")?;
+    let seats = get_seats_offered(client).await?;
+
+    let list = bidders
+        .into_iter()
+        .zip(bids)
+        .enumerate()
+        .map(|(i, (address, bid))| {
+            Ok(serde_json::json!({
+              "address": address,
+              "bid": bid.parse::<u64>()?,
+              "winning": (i as u64) < seats,
+            }))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(serde_json::json!(list))
+}
+
+/// Most validators looked up at once by ValidatorUniverse, each of which
+/// makes two view requests.
+pub const VALIDATOR_UNIVERSE_CONCURRENCY: usize = 8;
+
+/// The validator universe, each validator with its status: active if in
+/// the current validator set, else jailed or candidate. Also the jail and
+/// proof-of-fee bid state of each, for `limit` validators from `offset`.
+pub async fn get_validator_universe(
+    client: &Client,
+    offset: usize,
+    limit: Option<usize>,
+) -> anyhow::Result<serde_json::Value> {
+    let res = get_view(
+        client,
+        "0x1::validator_universe::get_eligible_validators",
+        None,
+        None,
+    )
+    .await?;
+    let (eligible,): (Vec<String>,) =
+        serde_json::from_value(res).context("unexpected response from get_eligible_validators")?;
+    let res = get_view(client, "0x1::stake::get_current_validators", None, None).await?;
+    let (current,): (Vec<String>,) =
+        serde_json::from_value(res).context("unexpected response from get_current_validators")?;
+
+    let total = eligible.len();
+    let page = eligible
+        .into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX));
+
+    let requests = page.map(|addr| {
+        let is_active = current.contains(&addr);
+        async move {
+            let args = Some(addr.clone());
+            let res = get_view(client, "0x1::jail::is_jailed", None, args.clone()).await?;
+            let (jailed,): (bool,) =
+                serde_json::from_value(res).context("unexpected response from is_jailed")?;
+            let res = get_view(client, "0x1::proof_of_fee::current_bid", None, args).await?;
+            let (bid, expiration): (String, String) =
+                serde_json::from_value(res).context("unexpected response from current_bid")?;
+
+            let status = if is_active {
+                "active"
+            } else if jailed {
+                "jailed"
+            } else {
+                "candidate"
+            };
+            Ok(serde_json::json!({
+              "address": addr,
+              "status": status,
+              "jailed": jailed,
+              "bid": bid.parse::<u64>()?,
+              "bid_expiration_epoch": expiration.parse::<u64>()?,
+            }))
+        }
+    });
+    let validators = stream::iter(requests)
+        .buffered(VALIDATOR_UNIVERSE_CONCURRENCY)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    Ok(serde_json::json!({
+      "total": total,
+      "validators": validators,
+    }))
+}
+
+/// The community wallets qualified for matching donations, each with its
+/// index of recent-weighted donations and its share of the match.
+pub async fn get_match_index(client: &Client) -> anyhow::Result<serde_json::Value> {
+    let res = retry(|| {
+        client
+            .get_account_resource(AccountAddress::ONE, "0x1::match_index::MatchIndex")
+            .map_err(anyhow::Error::from)
+    })
+    .await?
+    .into_inner();
+    // not initialized, so nothing will be matched
+    let data = match res {
+        Some(r) => r.data,
+        None => return Ok(serde_json::json!({ "total_index": 0, "wallets": [] })),
+    };
+
+    let addrs: Vec<String> = serde_json::from_value(data["addr"].clone())?;
+    let index: Vec<String> = serde_json::from_value(data["index"].clone())?;
+    let ratio: Vec<serde_json::Value> = serde_json::from_value(data["ratio"].clone())?;
+
+    let mut total_index = 0u128;
+    let mut wallets = vec![];
+    for (i, addr) in addrs.into_iter().enumerate() {
+        let idx = index
+            .get(i)
+            .map(|s| s.parse::<u64>())
+            .transpose()?
+            .unwrap_or(0);
+        // a FixedPoint32 is the raw value over 2^32
+        let raw = ratio
+            .get(i)
+            .and_then(|r| r["value"].as_str())
+            .map(|s| s.parse::<u64>())
+            .transpose()?
+            .unwrap_or(0);
+        total_index += idx as u128;
+        wallets.push(serde_json::json!({
+          "address": addr,
+          "index": idx,
+          "ratio": raw as f64 / (1u64 << 32) as f64,
+        }));
+    }
+
+    Ok(serde_json::json!({
+      "total_index": total_index,
+      "wallets": wallets,
+    }))
+}
+
+async fn get_seats_offered(client: &Client) -> anyhow::Result<u64> {
+    let res = get_view(client, "0x1::epoch_boundary::get_seats_offered", None, None).await?;
+    Ok(serde_json::from_value::<Vec<String>>(res)?
+        .first()
+        .context("could not get a response from view function get_seats_offered")?
+        .parse::<u64>()?)
+}
+
 /// Retrieves the current blockchain height.
 pub async fn epoch_over_can_trigger(client: &Client) -> anyhow::Result<bool> {
     let res = get_view(client, "0x1::epoch_boundary::can_trigger", None, None).await?;
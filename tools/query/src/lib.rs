@@ -1,5 +1,8 @@
 pub mod account_queries;
 pub mod chain_queries;
 pub mod query_cli;
+pub mod query_error;
+pub mod query_format;
+pub mod query_retry;
 pub mod query_type;
 pub mod query_view;
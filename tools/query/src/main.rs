@@ -1,8 +1,11 @@
-use anyhow::Result;
 use clap::Parser;
-use libra_query::query_cli::QueryCli;
+use libra_query::{query_cli::QueryCli, query_error::report_error};
+use std::process::exit;
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    QueryCli::parse().run().await
+async fn main() {
+    let cli = QueryCli::parse();
+    if let Err(e) = cli.run().await {
+        exit(report_error(&e, cli.error_json));
+    }
 }
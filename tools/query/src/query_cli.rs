@@ -1,13 +1,19 @@
 use std::path::PathBuf;
 
-use crate::query_type::QueryType;
+use crate::{
+    query_error::QueryError,
+    query_format::{render, OutputFormat},
+    query_retry::{retry, set_retry_policy, DEFAULT_RETRIES, DEFAULT_RETRY_DELAY_MS},
+    query_type::QueryType,
+};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::Parser;
+use futures::TryFutureExt;
 use libra_types::{
     core_types::app_cfg::AppCfg, exports::Client, type_extensions::client_ext::ClientExt,
 };
-use serde_json;
+use serde_json::Value;
 use url::Url;
 
 #[derive(Parser)]
@@ -26,6 +32,22 @@ pub struct QueryCli {
     /// Otherwise will default to what is in the config file
     #[clap(short, long)]
     pub url: Option<Url>,
+
+    /// optional, on failure print the error kind and message as json to stdout
+    #[clap(long)]
+    pub error_json: bool,
+
+    /// optional, print the result as json, an aligned table, or csv
+    #[clap(long, value_enum, default_value_t = OutputFormat::Json)]
+    pub format: OutputFormat,
+
+    /// optional, how many times to retry a request after a transient failure of the node
+    #[clap(long, default_value_t = DEFAULT_RETRIES)]
+    pub retries: u32,
+
+    /// optional, delay before the first retry in milliseconds, doubling after each attempt
+    #[clap(long, default_value_t = DEFAULT_RETRY_DELAY_MS)]
+    pub retry_delay_ms: u64,
 }
 
 impl QueryCli {
@@ -37,21 +59,45 @@ impl QueryCli {
         // The user can set an alternative path the the config,
         // which is useful in testnets.
 
+        set_retry_policy(self.retries, self.retry_delay_ms);
+
         // Initialize client
         let client = if let Some(u) = &self.url {
-            Client::new(u.clone())
+            client_from_url(u).await?
         } else if let Some(p) = &self.config_path {
             let app_cfg = AppCfg::load(Some(p.to_owned()))?;
             let (c, _) = Client::from_libra_config(&app_cfg, None).await?;
             c
         } else {
-            Client::default().await?
+            Client::default()
+                .await
+                .map_err(QueryError::NodeUnreachable)?
         };
 
         let res = self.subcommand.query_to_json(&client).await?;
-        let pretty_json = serde_json::to_string_pretty(&res)?;
-        println!("{}", pretty_json);
+        match (&self.subcommand, &res) {
+            // the bare waypoint, so scripts don't need to unquote it
+            (QueryType::Waypoint, Value::String(w)) => println!("{}", w),
+            _ => println!("{}", render(&res, self.format)?),
+        }
 
         Ok(())
     }
 }
+
+/// A client for the node at `url`. Checks the node responds before any query
+/// is made, so a wrong --url fails with a clear error.
+pub async fn client_from_url(url: &Url) -> Result<Client, QueryError> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(QueryError::BadInput(anyhow!(
+            "the node url must be http or https, got {url}"
+        )));
+    }
+    let client = Client::new(url.clone());
+    retry(|| client.get_index().map_err(anyhow::Error::from))
+        .await
+        .map_err(|e| {
+            QueryError::NodeUnreachable(e.context(format!("no node reachable at {url}")))
+        })?;
+    Ok(client)
+}
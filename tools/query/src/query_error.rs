@@ -0,0 +1,192 @@
+//! Exit codes and machine readable errors for the query and txs tools.
+//! Scripts can branch on the process exit code, or pass `--error-json`
+//! to get the error kind and message on stdout.
+//!
+//! Exit codes: 0 success, 1 other failure, 2 node unreachable,
+//! 3 not found, 4 bad input, 5 on-chain abort.
+
+use diem_sdk::{
+    move_types::account_address::AccountAddressParseError,
+    rest_client::{diem_api_types::DiemErrorCode, error::RestError},
+};
+use serde_json::json;
+
+/// The error returned by `QueryType::query_to_json`, so that callers can tell
+/// a node which is down from a resource which does not exist.
+#[derive(Debug, thiserror::Error)]
+pub enum QueryError {
+    /// the node could not be reached or timed out, worth trying again
+    #[error(transparent)]
+    NodeUnreachable(anyhow::Error),
+    /// the account, resource, or key does not exist
+    #[error(transparent)]
+    ResourceNotFound(anyhow::Error),
+    /// the node responded, but not with what we expected
+    #[error(transparent)]
+    DecodeFailed(anyhow::Error),
+    /// the arguments could not be parsed or were rejected by the node
+    #[error(transparent)]
+    BadInput(anyhow::Error),
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl QueryError {
+    /// the class of failure, and so the exit code
+    pub fn exit(&self) -> QueryExit {
+        match self {
+            QueryError::NodeUnreachable(_) => QueryExit::Connection,
+            QueryError::ResourceNotFound(_) => QueryExit::NotFound,
+            QueryError::DecodeFailed(_) => QueryExit::Failure,
+            QueryError::BadInput(_) => QueryExit::BadInput,
+            QueryError::Other(e) => QueryExit::from_error(e),
+        }
+    }
+}
+
+impl From<anyhow::Error> for QueryError {
+    fn from(err: anyhow::Error) -> Self {
+        if is_decode_error(&err) {
+            return QueryError::DecodeFailed(err);
+        }
+        match QueryExit::from_error(&err) {
+            QueryExit::Connection => QueryError::NodeUnreachable(err),
+            QueryExit::NotFound => QueryError::ResourceNotFound(err),
+            QueryExit::BadInput => QueryError::BadInput(err),
+            _ => QueryError::Other(err),
+        }
+    }
+}
+
+fn is_decode_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|c| {
+        c.downcast_ref::<serde_json::Error>().is_some()
+            || matches!(
+                c.downcast_ref::<RestError>(),
+                Some(RestError::Bcs(_)) | Some(RestError::Json(_))
+            )
+    })
+}
+
+/// The classes of failure a caller may want to handle differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryExit {
+    /// the command completed
+    Success,
+    /// anything we could not classify
+    Failure,
+    /// the account, resource, or transaction does not exist
+    NotFound,
+    /// the node could not be reached or timed out
+    Connection,
+    /// the arguments could not be parsed or were rejected by the node
+    BadInput,
+    /// the transaction was committed but aborted on chain
+    OnChainAbort,
+}
+
+impl QueryExit {
+    /// process exit code for this class of error
+    pub fn code(&self) -> i32 {
+        match self {
+            QueryExit::Success => 0,
+            QueryExit::Failure => 1,
+            QueryExit::Connection => 2,
+            QueryExit::NotFound => 3,
+            QueryExit::BadInput => 4,
+            QueryExit::OnChainAbort => 5,
+        }
+    }
+
+    /// stable name of the error kind, used in the json output
+    pub fn kind(&self) -> &'static str {
+        match self {
+            QueryExit::Success => "success",
+            QueryExit::Failure => "failure",
+            QueryExit::NotFound => "not_found",
+            QueryExit::Connection => "connection",
+            QueryExit::BadInput => "bad_input",
+            QueryExit::OnChainAbort => "on_chain_abort",
+        }
+    }
+
+    /// Walk the error chain and find the most specific class of failure.
+    pub fn from_error(err: &anyhow::Error) -> Self {
+        if let Some(q) = err.downcast_ref::<QueryError>() {
+            return q.exit();
+        }
+        for cause in err.chain() {
+            if let Some(rest) = cause.downcast_ref::<RestError>() {
+                return Self::from_rest_error(rest);
+            }
+            if cause.downcast_ref::<url::ParseError>().is_some()
+                || cause.downcast_ref::<AccountAddressParseError>().is_some()
+            {
+                return QueryExit::BadInput;
+            }
+        }
+
+        // errors raised by the query tools themselves carry a prefix
+        let msg = err.to_string();
+        if msg.starts_with("account_not_found")
+            || msg.starts_with("resource_not_found")
+            || msg.starts_with("key_not_found")
+        {
+            return QueryExit::NotFound;
+        }
+        if is_abort_message(&format!("{:#}", err)) {
+            return QueryExit::OnChainAbort;
+        }
+
+        QueryExit::Failure
+    }
+
+    fn from_rest_error(rest: &RestError) -> Self {
+        match rest {
+            RestError::Api(r) => match r.error.error_code {
+                DiemErrorCode::AccountNotFound
+                | DiemErrorCode::ResourceNotFound
+                | DiemErrorCode::ModuleNotFound
+                | DiemErrorCode::StructFieldNotFound
+                | DiemErrorCode::VersionNotFound
+                | DiemErrorCode::TransactionNotFound
+                | DiemErrorCode::TableItemNotFound
+                | DiemErrorCode::BlockNotFound => QueryExit::NotFound,
+                DiemErrorCode::InvalidInput => QueryExit::BadInput,
+                DiemErrorCode::VmError => QueryExit::OnChainAbort,
+                _ => QueryExit::Failure,
+            },
+            RestError::UrlParse(_) => QueryExit::BadInput,
+            RestError::Bcs(_) | RestError::Json(_) => QueryExit::Failure,
+            // the client reports a failed transaction as an unknown error
+            RestError::Unknown(e) if is_abort_message(&e.to_string()) => QueryExit::OnChainAbort,
+            // timeouts, refused connections, and http errors
+            _ => QueryExit::Connection,
+        }
+    }
+}
+
+fn is_abort_message(msg: &str) -> bool {
+    msg.contains("MoveAbort") || msg.contains("ABORTED") || msg.contains("EXECUTION_FAILURE")
+}
+
+/// Print the error for the user, and return the exit code the process
+/// should terminate with.
+/// With `json` the error is printed to stdout as
+/// `{"error": {"kind": .., "code": .., "message": ..}}`.
+pub fn report_error(err: &anyhow::Error, json: bool) -> i32 {
+    let exit = QueryExit::from_error(err);
+    if json {
+        let out = json!({
+            "error": {
+                "kind": exit.kind(),
+                "code": exit.code(),
+                "message": format!("{:#}", err),
+            }
+        });
+        println!("{}", out);
+    } else {
+        eprintln!("ERROR: {:#}", err);
+    }
+    exit.code()
+}
@@ -0,0 +1,154 @@
+//! Render query results as json, an aligned table, or csv.
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// pretty printed json
+    #[default]
+    Json,
+    /// aligned columns for the terminal
+    Table,
+    /// comma separated values for spreadsheets
+    Csv,
+}
+
+/// Render a query result in the chosen format.
+/// Flat objects (e.g. Balance, ValConfig) become a single row with the
+/// object keys as column headers, and arrays of flat objects (e.g. Txs, or
+/// a batch of balances) become one row per item.
+/// Any other shape falls back to pretty json.
+pub fn render(value: &Value, format: OutputFormat) -> anyhow::Result<String> {
+    if format == OutputFormat::Json {
+        return Ok(serde_json::to_string_pretty(value)?);
+    }
+
+    let (headers, rows) = match to_rows(value) {
+        Some(t) => t,
+        None => return Ok(serde_json::to_string_pretty(value)?),
+    };
+
+    match format {
+        OutputFormat::Table => Ok(to_table(&headers, &rows)),
+        OutputFormat::Csv => to_csv(&headers, &rows),
+        OutputFormat::Json => unreachable!(),
+    }
+}
+
+/// headers and rows of cells, if the value has a tabular shape
+fn to_rows(value: &Value) -> Option<(Vec<String>, Vec<Vec<String>>)> {
+    let items: Vec<&Value> = match value {
+        Value::Object(_) => vec![value],
+        Value::Array(a) if !a.is_empty() => a.iter().collect(),
+        _ => return None,
+    };
+
+    // rows may have different keys, e.g. a batch balance row marked not_found,
+    // so the columns are every key in order of first appearance
+    let mut headers: Vec<String> = vec![];
+    for item in &items {
+        for k in item.as_object()?.keys() {
+            if !headers.contains(k) {
+                headers.push(k.to_owned());
+            }
+        }
+    }
+
+    let mut rows = vec![];
+    for item in items {
+        let obj = item.as_object()?;
+        let row = headers
+            .iter()
+            .map(|h| to_cell(obj.get(h).unwrap_or(&Value::Null)))
+            .collect::<Option<Vec<String>>>()?;
+        rows.push(row);
+    }
+    Some((headers, rows))
+}
+
+/// a scalar, or a list of scalars such as network addresses
+fn to_cell(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => Some(String::new()),
+        Value::String(s) => Some(s.to_owned()),
+        Value::Bool(_) | Value::Number(_) => Some(value.to_string()),
+        Value::Array(a) => {
+            let cells = a
+                .iter()
+                .map(|v| match v {
+                    Value::Array(_) | Value::Object(_) => None,
+                    _ => to_cell(v),
+                })
+                .collect::<Option<Vec<String>>>()?;
+            Some(cells.join(" "))
+        }
+        Value::Object(_) => None,
+    }
+}
+
+fn to_table(headers: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.len());
+        }
+    }
+
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("{:<width$}", c, width = widths[i]))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let separator: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+
+    let mut out = vec![line(headers), line(&separator)];
+    out.extend(rows.iter().map(|r| line(r)));
+    out.join("\n")
+}
+
+fn to_csv(headers: &[String], rows: &[Vec<String>]) -> anyhow::Result<String> {
+    let mut wtr = csv::Writer::from_writer(vec![]);
+    wtr.write_record(headers)?;
+    for row in rows {
+        wtr.write_record(row)?;
+    }
+    let bytes = wtr.into_inner()?;
+    Ok(String::from_utf8(bytes)?.trim_end().to_string())
+}
+
+#[test]
+fn render_balance_table_and_csv() {
+    let v = serde_json::json!({ "unlocked": 10.5, "total": 100.0 });
+
+    let table = render(&v, OutputFormat::Table).unwrap();
+    assert_eq!(table, "unlocked  total\n--------  -----\n10.5      100.0");
+
+    let csv = render(&v, OutputFormat::Csv).unwrap();
+    assert_eq!(csv, "unlocked,total\n10.5,100.0");
+}
+
+#[test]
+fn render_nested_falls_back_to_json() {
+    let v = serde_json::json!({ "body": { "nested": true } });
+    let out = render(&v, OutputFormat::Table).unwrap();
+    assert_eq!(out, serde_json::to_string_pretty(&v).unwrap());
+}
+
+#[test]
+fn render_batch_csv_with_missing_columns() {
+    let v = serde_json::json!([
+        { "address": "0x1", "not_found": true },
+        { "address": "0x2", "unlocked": 1.0, "total": 2.0 },
+    ]);
+    let csv = render(&v, OutputFormat::Csv).unwrap();
+    assert_eq!(
+        csv,
+        "address,not_found,unlocked,total\n0x1,true,,\n0x2,,1.0,2.0"
+    );
+}
@@ -0,0 +1,141 @@
+//! Retry transient failures of the upstream node, so that a node briefly
+//! returning 503 or timing out doesn't fail a whole query.
+
+use diem_sdk::rest_client::{diem_api_types::DiemErrorCode, error::RestError};
+use rand::Rng;
+use reqwest::StatusCode;
+use std::{
+    future::Future,
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+    time::Duration,
+};
+
+pub const DEFAULT_RETRIES: u32 = 3;
+pub const DEFAULT_RETRY_DELAY_MS: u64 = 500;
+
+static RETRIES: AtomicU32 = AtomicU32::new(DEFAULT_RETRIES);
+static RETRY_DELAY_MS: AtomicU64 = AtomicU64::new(DEFAULT_RETRY_DELAY_MS);
+
+/// Set the retry policy used by the query helpers, e.g. from the cli flags.
+pub fn set_retry_policy(retries: u32, base_delay_ms: u64) {
+    RETRIES.store(retries, Ordering::Relaxed);
+    RETRY_DELAY_MS.store(base_delay_ms, Ordering::Relaxed);
+}
+
+/// Call `f`, and on a transient failure call it again up to `retries` more
+/// times, sleeping with jittered exponential backoff in between. Other
+/// errors are returned at once.
+pub async fn with_retry<F, Fut, T>(
+    retries: u32,
+    base_delay: Duration,
+    mut f: F,
+) -> anyhow::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let mut retry = 0;
+    loop {
+        match f().await {
+            Ok(r) => return Ok(r),
+            Err(e) if retry < retries && is_transient(&e) => {
+                let backoff = base_delay.as_millis() as u64 * 2u64.pow(retry);
+                let jitter = rand::thread_rng().gen_range(0, backoff / 2 + 1);
+                tokio::time::sleep(Duration::from_millis(backoff + jitter)).await;
+                retry += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// `with_retry` using the policy set with `set_retry_policy`.
+pub async fn retry<F, Fut, T>(f: F) -> anyhow::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let retries = RETRIES.load(Ordering::Relaxed);
+    let delay = Duration::from_millis(RETRY_DELAY_MS.load(Ordering::Relaxed));
+    with_retry(retries, delay, f).await
+}
+
+/// Timeouts, connection errors, rate limiting, and 5xx responses are worth
+/// another try.
+/// A missing resource, a rejected request, or a response we can't decode
+/// will not get better.
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    let rest = match err.chain().find_map(|c| c.downcast_ref::<RestError>()) {
+        Some(r) => r,
+        None => return false,
+    };
+
+    match rest {
+        RestError::Api(r) => matches!(
+            r.error.error_code,
+            DiemErrorCode::InternalError
+                | DiemErrorCode::HealthCheckFailed
+                | DiemErrorCode::MempoolIsFull
+        ),
+        RestError::Http(status, _) => {
+            status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+        }
+        RestError::Timeout(_) => true,
+        // the client reports transport failures as unknown errors, along
+        // with anything else it can't classify
+        RestError::Unknown(e) => e
+            .chain()
+            .filter_map(|c| c.downcast_ref::<reqwest::Error>())
+            .any(|r| r.is_timeout() || r.is_connect()),
+        _ => false,
+    }
+}
+
+#[tokio::test]
+async fn retries_transient_errors_only() {
+    use std::sync::atomic::AtomicUsize;
+
+    // a timeout is retried until it succeeds
+    let calls = AtomicUsize::new(0);
+    let res = with_retry(3, Duration::from_millis(1), || async {
+        if calls.fetch_add(1, Ordering::SeqCst) < 3 {
+            return Err(anyhow::Error::from(RestError::Timeout("test")));
+        }
+        Ok(7)
+    })
+    .await;
+    assert_eq!(res.unwrap(), 7);
+    // the first attempt and three retries
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+    // so is a rate limited request, but not a bad request
+    let http = |status| {
+        let e = reqwest::Client::new().get("not a url").build().unwrap_err();
+        anyhow::Error::from(RestError::Http(status, e))
+    };
+    assert!(is_transient(&http(StatusCode::TOO_MANY_REQUESTS)));
+    assert!(is_transient(&http(StatusCode::SERVICE_UNAVAILABLE)));
+    assert!(!is_transient(&http(StatusCode::BAD_REQUEST)));
+
+    // an error the client could not classify is not retried
+    let calls = AtomicUsize::new(0);
+    let res: anyhow::Result<()> = with_retry(3, Duration::from_millis(1), || async {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Err(anyhow::Error::from(RestError::Unknown(anyhow::anyhow!(
+            "unexpected"
+        ))))
+    })
+    .await;
+    assert!(res.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // a decoding error is returned without retrying
+    let calls = AtomicUsize::new(0);
+    let res: anyhow::Result<()> = with_retry(3, Duration::from_millis(1), || async {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Err(anyhow::anyhow!("cannot decode"))
+    })
+    .await;
+    assert!(res.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
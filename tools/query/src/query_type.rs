@@ -1,41 +1,161 @@
 use crate::{
     account_queries::{
-        community_wallet_scheduled_transactions, community_wallet_signers,
-        get_account_balance_libra, get_events, get_transactions, get_val_config,
-        is_community_wallet_migrated,
+        account_exists, community_wallet_scheduled_transactions, community_wallet_signers,
+        get_account_balance_detailed, get_account_resource_types, get_all_fees_made,
+        get_all_resources, get_ancestry, get_balances_batch, get_coin_decimals, get_events,
+        get_fee_maker, get_pledges, get_pof_bid, get_receipts, get_resource_field,
+        get_rotation_history, get_slow_wallet, get_transactions, get_val_config_json,
+        get_vouch_limits, get_vouches, is_community_wallet_migrated, read_account_file,
+        tx_entry_function, BALANCE_BATCH_CONCURRENCY, BALANCE_BATCH_DEFAULT_CONCURRENCY,
+        TXS_MAX_PAGES, TXS_PAGE_SIZE,
     },
-    chain_queries::{get_epoch, get_height},
+    chain_queries::{
+        get_epoch, get_epoch_waypoint, get_ledger_height, get_match_index, get_pof_auction,
+        get_pof_bidders, get_seconds_to_next_epoch, get_sync_delay, get_validator_universe,
+    },
+    query_error::QueryError,
+    query_retry::retry,
     query_view::get_view,
 };
 use anyhow::{bail, Context, Result};
-use diem_api_types::Transaction;
 use diem_debugger::DiemDebugger;
 use diem_sdk::{rest_client::Client, types::account_address::AccountAddress};
+use futures::TryFutureExt;
 use indoc::indoc;
-use libra_types::{exports::AuthenticationKey, type_extensions::client_ext::ClientExt};
+use libra_types::{
+    core_types::network_playlist::NetworkPlaylist,
+    exports::{AuthenticationKey, NamedChain},
+    type_extensions::client_ext::ClientExt,
+};
 use serde_json::json;
+use std::path::PathBuf;
+use url::Url;
 
 #[derive(Debug, clap::Subcommand)]
 pub enum QueryType {
-    /// Account balance
+    /// Account balance, and the unlocked portion of a slow wallet
     Balance {
-        /// account to query txs of
+        /// account to query the balance of
+        #[clap(required_unless_present = "account_file")]
+        account: Option<AccountAddress>,
+        #[clap(long)]
+        /// file of newline separated addresses, to look up many balances at once
+        account_file: Option<PathBuf>,
+        #[clap(long)]
+        /// also return the unscaled balance, in the coin's base units
+        raw: bool,
+    },
+    /// Balances of every address in a newline separated file
+    BalanceBatch {
+        /// file of addresses, blank lines and # comments are skipped
+        path: PathBuf,
+        #[clap(long)]
+        /// how many requests in flight, defaults to 8
+        concurrency: Option<usize>,
+    },
+    /// Unlocked and locked coins of a slow wallet
+    SlowWallet {
+        /// slow wallet account
+        account: AccountAddress,
+    },
+    /// Vouches an account received and gave, with the epoch of each
+    Vouches {
+        /// account to query the vouches of
+        account: AccountAddress,
+    },
+    /// Who vouched for an account, and how many vouches each has given
+    Vouch {
+        /// account to query the vouchers of
         account: AccountAddress,
     },
+    /// Pledges an account has made, and whether each can be revoked
+    Pledge {
+        /// account which made the pledges
+        account: AccountAddress,
+    },
+    /// Donations an account has made to each destination
+    Receipts {
+        /// account which made the donations
+        account: AccountAddress,
+    },
+    /// Fees an account has paid, or with --all the fees of every account
+    /// this epoch
+    FeeMaker {
+        /// account which paid the fees
+        #[clap(required_unless_present = "all")]
+        account: Option<AccountAddress>,
+        #[clap(long)]
+        /// network wide fees made this epoch
+        all: bool,
+    },
+    /// Ancestors of an account, from the root to the parent
+    Ancestry {
+        /// account to query the ancestry of
+        account: AccountAddress,
+    },
+    /// A validator's proof-of-fee bid, and when it expires, or with --all
+    /// every qualified bid in auction order
+    #[clap(alias = "pof")]
+    PofBid {
+        /// validator account
+        #[clap(required_unless_present = "all")]
+        account: Option<AccountAddress>,
+        #[clap(long)]
+        /// all qualified bidders, and which win a seat next epoch
+        all: bool,
+    },
+    /// The proof-of-fee auction clearing price and seats offered
+    PofAuction,
+    /// Every validator in the validator universe, with its status, jail
+    /// and proof-of-fee state
+    ValidatorUniverse {
+        #[clap(long, default_value_t = 0)]
+        /// skip this many validators
+        offset: usize,
+        #[clap(long)]
+        /// return at most this many validators
+        limit: Option<usize>,
+    },
+    /// Community wallets which receive matching funds, and the share of each
+    MatchIndex,
     /// A validator's on-chain configuration
     ValConfig {
         /// account to query txs of
         account: AccountAddress,
+        #[clap(long)]
+        /// fail if a field can't be decoded, instead of showing its raw hex
+        strict: bool,
     },
-    /// Epoch and waypoint
+    /// Epoch, waypoint, and estimated seconds until the next epoch
     Epoch,
-    /// Query any account resource by access path string
+    /// Only the current waypoint, e.g. to configure a fresh node
+    Waypoint,
+    /// Query any account resource by access path string, or every resource
+    /// of the account with --all
     Resource {
         /// account to query txs of
         account: AccountAddress,
-        #[clap(short, long)]
+        #[clap(short, long, required_unless_present = "all")]
         /// the path of the resource, such as 0x1::slow_wallet::SlowWallet
-        resource_path_string: String,
+        resource_path_string: Option<String>,
+        #[clap(long)]
+        /// all resources of the account, keyed by resource type
+        all: bool,
+    },
+    /// Read a single field of an account resource, such as the unlocked
+    /// field of 0x1::slow_wallet::SlowWallet
+    MoveValue {
+        /// account holding the resource
+        account: AccountAddress,
+        #[clap(long)]
+        /// module of the resource, such as slow_wallet
+        module_name: String,
+        #[clap(long)]
+        /// name of the resource struct, such as SlowWallet
+        struct_name: String,
+        #[clap(long)]
+        /// field of the struct to return, such as unlocked
+        key_name: String,
     },
     /// Execute a View function on-chain
     View {
@@ -79,10 +199,30 @@ pub enum QueryType {
     LookupAddress {
         auth_key: AuthenticationKey, // we use account address to parse, because that's the format needed to lookup users. AuthKeys and AccountAddress are the same formats.
     },
+    /// The current authentication key of an account, the reverse of
+    /// LookupAddress. Notes if it differs from the address, i.e. the key was
+    /// rotated
+    AuthKey {
+        /// account to query the key of
+        account: AccountAddress,
+    },
+    /// The current authentication key of an account, and every key rotation
+    RotationHistory {
+        /// account to query the key history of
+        account: AccountAddress,
+    },
     /// Network block height
     BlockHeight,
     /// How far behind the local is from the upstream nodes
-    SyncDelay,
+    SyncDelay {
+        #[clap(long)]
+        /// upstream node to compare against, can be repeated.
+        /// Otherwise uses the network's default playlist
+        upstream: Vec<Url>,
+        #[clap(long)]
+        /// network of the default playlist, defaults to MAINNET
+        chain_name: Option<NamedChain>,
+    },
     /// Get events
     Events {
         /// account to query events
@@ -99,13 +239,15 @@ pub enum QueryType {
         /// account to query txs of
         account: AccountAddress,
         #[clap(long)]
-        /// get transactions after this height
+        /// get transactions starting from this account sequence number
         txs_height: Option<u64>,
         #[clap(long)]
-        /// limit how many txs
+        /// limit how many txs, defaults to 100 and at most 1000
         txs_count: Option<u64>,
         #[clap(long)]
-        /// filter by type
+        /// only transactions whose entry function contains this, such as ol_account::transfer.
+        /// Reads at most 5000 transactions; if it stops early the height to
+        /// continue from is printed to stderr
         txs_type: Option<String>,
     },
     /// Is the community wallet migrated
@@ -128,11 +270,115 @@ pub enum QueryType {
 }
 
 impl QueryType {
-    pub async fn query_to_json(&self, client: &Client) -> Result<serde_json::Value> {
+    /// Run the query. The error says whether the node was unreachable, the
+    /// resource was not found, or the response could not be decoded.
+    pub async fn query_to_json(&self, client: &Client) -> Result<serde_json::Value, QueryError> {
+        Ok(self.run_query(client).await?)
+    }
+
+    async fn run_query(&self, client: &Client) -> Result<serde_json::Value> {
         match self {
-            QueryType::Balance { account } => {
-                let res = get_account_balance_libra(client, *account).await?;
-                Ok(json!(res.scaled()))
+            QueryType::Balance {
+                account,
+                account_file,
+                raw,
+            } => {
+                if let Some(p) = account_file {
+                    let accounts = read_account_file(p)?;
+                    let res =
+                        get_balances_batch(client, &accounts, BALANCE_BATCH_CONCURRENCY).await;
+                    let list: Vec<_> = res
+                        .into_iter()
+                        .map(|(a, r)| match r {
+                            Ok(Some(b)) => {
+                                let scaled = b.scaled();
+                                json!({
+                                  "address": a.to_hex_literal(),
+                                  "unlocked": scaled.unlocked,
+                                  "total": scaled.total,
+                                })
+                            }
+                            Ok(None) => json!({ "address": a.to_hex_literal(), "not_found": true }),
+                            Err(e) => {
+                                json!({ "address": a.to_hex_literal(), "error": e.to_string() })
+                            }
+                        })
+                        .collect();
+                    return Ok(json!(list));
+                }
+                let account = account.context("an account or --account-file is required")?;
+                let (res, is_slow) = get_account_balance_detailed(client, account).await?;
+                let decimals = get_coin_decimals(client).await?;
+                let scaled = res.scaled_with_decimals(decimals);
+                // the unlocked amount only means something for a slow wallet
+                let (scaled, unscaled) = if is_slow {
+                    (
+                        json!({ "unlocked": scaled.unlocked, "total": scaled.total }),
+                        json!({ "unlocked": res.unlocked, "total": res.total }),
+                    )
+                } else {
+                    (
+                        json!({ "total": scaled.total }),
+                        json!({ "total": res.total }),
+                    )
+                };
+                if !*raw {
+                    return Ok(scaled);
+                }
+                Ok(json!({ "scaled": scaled, "unscaled": unscaled }))
+            }
+            QueryType::BalanceBatch { path, concurrency } => {
+                let accounts = read_account_file(path)?;
+                let res = get_balances_batch(
+                    client,
+                    &accounts,
+                    concurrency.unwrap_or(BALANCE_BATCH_DEFAULT_CONCURRENCY),
+                )
+                .await;
+                let list: Vec<_> = res
+                    .into_iter()
+                    .map(|(a, r)| match r {
+                        Ok(Some(b)) => {
+                            json!({ "address": a.to_hex_literal(), "balance": b.scaled() })
+                        }
+                        Ok(None) => json!({
+                          "address": a.to_hex_literal(),
+                          "balance": null,
+                          "error": "account_not_found",
+                        }),
+                        Err(e) => json!({
+                          "address": a.to_hex_literal(),
+                          "balance": null,
+                          "error": e.to_string(),
+                        }),
+                    })
+                    .collect();
+                Ok(json!(list))
+            }
+            QueryType::SlowWallet { account } => get_slow_wallet(client, *account).await,
+            QueryType::Vouches { account } => get_vouches(client, *account).await,
+            QueryType::Vouch { account } => get_vouch_limits(client, *account).await,
+            QueryType::Pledge { account } => get_pledges(client, *account).await,
+            QueryType::Receipts { account } => get_receipts(client, *account).await,
+            QueryType::FeeMaker { account, all } => {
+                if *all {
+                    return get_all_fees_made(client).await;
+                }
+                let account = account.context("an account or --all is required")?;
+                get_fee_maker(client, account).await
+            }
+            QueryType::Ancestry { account } => get_ancestry(client, *account).await,
+            QueryType::PofBid { account, all } => {
+                if *all {
+                    return get_pof_bidders(client).await;
+                }
+                let account = account.context("an account or --all is required")?;
+                get_pof_bid(client, account).await
+            }
+            QueryType::PofAuction => get_pof_auction(client).await,
+            QueryType::MatchIndex => get_match_index(client).await,
+            QueryType::ValidatorUniverse { offset, limit } => {
+                get_validator_universe(client, *offset, *limit).await
             }
             QueryType::View {
                 function_id,
@@ -146,11 +392,21 @@ impl QueryType {
             }
             QueryType::Epoch => {
                 let num = get_epoch(client).await?;
-                let json = json!({
+                let waypoint = get_epoch_waypoint(client).await?;
+                let mut json = json!({
                   "epoch": num,
+                  "waypoint": waypoint.to_string(),
                 });
+                // older nodes may not have the views to estimate this, so it is optional
+                if let Ok(secs) = get_seconds_to_next_epoch(client).await {
+                    json["seconds_to_next_epoch"] = secs.into();
+                }
                 Ok(json)
             }
+            QueryType::Waypoint => {
+                let waypoint = get_epoch_waypoint(client).await?;
+                Ok(json!(waypoint.to_string()))
+            }
             QueryType::LookupAddress { auth_key } => {
                 let addr = client
                     .lookup_originating_address(auth_key.to_owned())
@@ -158,34 +414,111 @@ impl QueryType {
 
                 Ok(json!({ "address": addr }))
             }
+            QueryType::AuthKey { account } => {
+                let auth_key = get_resource_field(
+                    client,
+                    *account,
+                    "0x1::account::Account",
+                    "authentication_key",
+                )
+                .await?;
+                let key = auth_key
+                    .as_str()
+                    .context("authentication_key is not a hex string")?;
+                // an account starts with its address as the auth key
+                let rotated = AccountAddress::from_hex_literal(key)? != *account;
+                Ok(json!({
+                  "auth_key": auth_key,
+                  "rotated": rotated,
+                }))
+            }
             QueryType::Resource {
                 account,
                 resource_path_string,
+                all,
             } => {
-                let res = client
-                    .get_account_resource(*account, resource_path_string)
-                    .await?;
+                if *all {
+                    if !account_exists(client, *account).await? {
+                        bail!("account_not_found: there is no account at address {account}");
+                    }
+                    return get_all_resources(client, *account).await;
+                }
+                let resource_path_string = resource_path_string
+                    .as_deref()
+                    .context("a resource path or --all is required")?;
+                let res = retry(|| {
+                    client
+                        .get_account_resource(*account, resource_path_string)
+                        .map_err(anyhow::Error::from)
+                })
+                .await?;
 
                 if let Some(r) = res.inner() {
-                    Ok(r.data.clone())
-                } else {
-                    bail!("no resource {resource_path_string}, found at address {account}");
+                    return Ok(r.data.clone());
                 }
-            }
-            QueryType::ValConfig { account } => {
-                let res = get_val_config(client, *account).await?;
 
-                // make this readable, turn the network address into a string
+                // a missing account and a missing resource are different problems
+                if !account_exists(client, *account).await? {
+                    bail!("account_not_found: there is no account at address {account}");
+                }
+                let available = get_account_resource_types(client, *account).await?;
+                bail!(
+                    "resource_not_found: no resource {resource_path_string} at address {account}, available resources: {}",
+                    available.join(", ")
+                );
+            }
+            QueryType::MoveValue {
+                account,
+                module_name,
+                struct_name,
+                key_name,
+            } => {
+                let path = format!("0x1::{module_name}::{struct_name}");
+                get_resource_field(client, *account, &path, key_name).await
+            }
+            QueryType::ValConfig { account, strict } => {
+                get_val_config_json(client, *account, *strict).await
+            }
+            QueryType::RotationHistory { account } => {
+                let current = get_resource_field(
+                    client,
+                    *account,
+                    "0x1::account::Account",
+                    "authentication_key",
+                )
+                .await?;
+                let history = get_rotation_history(client, *account).await?;
                 Ok(json!({
-                  "consensus_public_key": res.consensus_public_key,
-                  "validator_network_addresses": res.validator_network_addresses().context("can't BCS decode the validator network address")?,
-                  "fullnode_network_addresses": res.fullnode_network_addresses().context("can't BCS decode the fullnode network address")?,
-                  "validator_index": res.validator_index,
+                  "current_auth_key": current,
+                  "rotations": history,
                 }))
             }
             QueryType::BlockHeight => {
-                let height = get_height(client).await?;
-                Ok(json!({ "BlockHeight": height }))
+                let (block_height, ledger_version) = get_ledger_height(client).await?;
+                Ok(json!({
+                  "block_height": block_height,
+                  "ledger_version": ledger_version,
+                }))
+            }
+            QueryType::SyncDelay {
+                upstream,
+                chain_name,
+            } => {
+                let urls = if upstream.is_empty() {
+                    NetworkPlaylist::default_for_network(*chain_name)
+                        .await?
+                        .all_urls()?
+                } else {
+                    upstream.to_owned()
+                };
+                let res = get_sync_delay(client, &urls).await?;
+                Ok(json!({
+                  "local_version": res.local_version,
+                  "upstream_version": res.upstream_version,
+                  "version_behind": res.version_behind,
+                  "seconds_behind": res.seconds_behind,
+                  "unreachable": res.unreachable,
+                }))
             }
             QueryType::Events {
                 account,
@@ -201,7 +534,7 @@ impl QueryType {
                 txs_count,
                 txs_type,
             } => {
-                let res: Vec<Transaction> = get_transactions(
+                let (res, resume_at) = get_transactions(
                     client,
                     *account,
                     *txs_height,
@@ -209,17 +542,28 @@ impl QueryType {
                     txs_type.to_owned(),
                 )
                 .await?;
-                let prune_res: Vec<_> = res
+                let summaries: Vec<_> = res
                     .iter()
                     .map(|tx| {
                         json!({
-                        "timestamp": tx.timestamp(),
-                        "version" : tx.version(),
-                        "is_pending" : tx.is_pending(),
+                          "hash": tx.transaction_info().ok().map(|i| i.hash.to_string()),
+                          "version": tx.version(),
+                          "function": tx_entry_function(tx),
+                          "success": tx.success(),
+                          "timestamp": tx.timestamp(),
                         })
                     })
                     .collect();
-                Ok(json!({ "transactions": prune_res }))
+                // the scan stopped before the end of the history, say where
+                // to continue from without changing the shape of the output
+                if let Some(h) = resume_at {
+                    eprintln!(
+                        "WARN: stopped after {} transactions, continue with --txs-height {}",
+                        TXS_MAX_PAGES * TXS_PAGE_SIZE,
+                        h
+                    );
+                }
+                Ok(json!(summaries))
             }
             QueryType::ComWalletMigrated { account } => {
                 let res = is_community_wallet_migrated(client, *account).await?;
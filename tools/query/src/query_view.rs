@@ -1,3 +1,4 @@
+use crate::query_retry::retry;
 use anyhow::Result;
 use diem_sdk::rest_client::Client;
 use libra_types::type_extensions::client_ext::ClientExt;
@@ -9,7 +10,7 @@ pub async fn get_view(
     type_args: Option<String>,
     args: Option<String>,
 ) -> Result<Value> {
-    client.view_ext(function_id, type_args, args).await
+    retry(|| client.view_ext(function_id, type_args.clone(), args.clone())).await
 }
 
 // helper to turn a serde_json value to string
@@ -1,6 +1,9 @@
-use libra_query::query_type::QueryType;
+use libra_query::{query_error::QueryExit, query_type::QueryType};
 use libra_smoke_tests::libra_smoke::LibraSmoke;
-use libra_types::move_resource::gas_coin::LibraBalanceDisplay;
+use libra_types::{
+    exports::{AccountAddress, Waypoint},
+    move_resource::gas_coin::LibraBalanceDisplay,
+};
 
 /// Testing the query library
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -12,7 +15,11 @@ async fn libra_query_test() {
 
     let c = s.client();
 
-    let q = QueryType::Balance { account: val_acct };
+    let q = QueryType::Balance {
+        account: Some(val_acct),
+        account_file: None,
+        raw: false,
+    };
     match q.query_to_json(&c).await {
         Ok(v) => {
             println!("v: {:?}", v);
@@ -27,6 +34,65 @@ async fn libra_query_test() {
     }
 }
 
+/// the raw balance includes the base units alongside the scaled value
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn libra_query_balance_raw() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::Balance {
+        account: Some(val_acct),
+        account_file: None,
+        raw: true,
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    let b: LibraBalanceDisplay = serde_json::from_value(v["scaled"].clone()).unwrap();
+    assert_eq!(b.unlocked, 1000.0);
+    assert_eq!(v["unscaled"]["unlocked"].as_u64().unwrap(), 1_000_000_000);
+    assert_eq!(v["unscaled"]["total"].as_u64().unwrap(), 1_000_000_000);
+}
+
+/// a slow wallet reports its unlocked portion, and an ordinary account
+/// only the total
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn balance_slow_wallet_unlocked() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    // genesis validators are slow wallets
+    let val_acct = s.first_account.address();
+    s.mint_and_unlock(val_acct, 1_000_000_000).await.unwrap();
+
+    let c = s.client();
+
+    let q = QueryType::Balance {
+        account: Some(val_acct),
+        account_file: None,
+        raw: false,
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert_eq!(v["total"].as_f64().unwrap(), 2000.0);
+
+    let q = QueryType::SlowWallet { account: val_acct };
+    let slow = q.query_to_json(&c).await.unwrap();
+    assert_eq!(v["unlocked"], slow["unlocked"]);
+
+    let rando = s.marlon_rando().address();
+    s.mint_and_unlock(rando, 1_000_000).await.unwrap();
+    let q = QueryType::Balance {
+        account: Some(rando),
+        account_file: None,
+        raw: false,
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert_eq!(v["total"].as_f64().unwrap(), 1.0);
+    assert!(v.get("unlocked").is_none());
+}
+
 /// test account struct annotation
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 async fn account_annotate_test() {
@@ -42,3 +108,517 @@ async fn account_annotate_test() {
     println!("{:#}", &res.as_str().unwrap());
     assert!(res.as_str().unwrap().contains("drop"));
 }
+
+/// a resource query on an address with no account reports the account as missing
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn resource_account_not_found() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+
+    let c = s.client();
+
+    let q = QueryType::Resource {
+        account: "0x1234".parse().unwrap(),
+        resource_path_string: Some("0x1::slow_wallet::SlowWallet".to_string()),
+        all: false,
+    };
+    let err = q.query_to_json(&c).await.unwrap_err();
+    assert!(err.to_string().contains("account_not_found"));
+}
+
+/// a resource query on an existing account lists what resources it does have
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn resource_not_found_lists_available() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::Resource {
+        account: val_acct,
+        resource_path_string: Some("0x1::pledge_accounts::MyPledges".to_string()),
+        all: false,
+    };
+    let err = q.query_to_json(&c).await.unwrap_err().to_string();
+    assert!(err.contains("resource_not_found"));
+    assert!(err.contains("0x1::account::Account"));
+}
+
+/// every resource of an account, keyed by type
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn resource_all_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::Resource {
+        account: val_acct,
+        resource_path_string: None,
+        all: true,
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v["0x1::account::Account"].is_object());
+    assert!(v["0x1::slow_wallet::SlowWallet"].is_object());
+}
+
+/// block height comes from the node's ledger info
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn block_height_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+
+    let c = s.client();
+
+    let q = QueryType::BlockHeight;
+    let res = q.query_to_json(&c).await.unwrap();
+    assert!(res["block_height"].as_u64().unwrap() > 0);
+    assert!(res["ledger_version"].as_u64().unwrap() > 0);
+}
+
+/// epoch, waypoint, and seconds_to_next_epoch of a running network
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn epoch_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+
+    let c = s.client();
+
+    let q = QueryType::Epoch;
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v["epoch"].as_u64().unwrap() > 0);
+    // a waypoint is formatted as version:hash
+    let waypoint = v["waypoint"].as_str().unwrap();
+    assert!(waypoint.parse::<Waypoint>().is_ok());
+    assert!(v["seconds_to_next_epoch"].as_u64().is_some());
+
+    let q = QueryType::Waypoint;
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v.as_str().unwrap().parse::<Waypoint>().is_ok());
+}
+
+/// sync delay against the same node, with one upstream that is down
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn sync_delay_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+
+    let c = s.client();
+
+    let q = QueryType::SyncDelay {
+        upstream: vec![
+            s.api_endpoint.clone(),
+            "http://127.0.0.1:1".parse().unwrap(),
+        ],
+        chain_name: None,
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v["upstream_version"].as_u64().unwrap() >= v["local_version"].as_u64().unwrap());
+    assert_eq!(v["unreachable"].as_array().unwrap().len(), 1);
+}
+
+/// read one field of a resource, and list the keys when it is missing
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn move_value_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::MoveValue {
+        account: val_acct,
+        module_name: "account".to_string(),
+        struct_name: "Account".to_string(),
+        key_name: "sequence_number".to_string(),
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v.is_string());
+
+    let q = QueryType::MoveValue {
+        account: val_acct,
+        module_name: "account".to_string(),
+        struct_name: "Account".to_string(),
+        key_name: "not_a_key".to_string(),
+    };
+    let err = q.query_to_json(&c).await.unwrap_err().to_string();
+    assert!(err.contains("key_not_found"));
+    assert!(err.contains("authentication_key"));
+}
+
+/// transaction history returns summaries, and filters on the entry function
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn txs_history_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::Txs {
+        account: val_acct,
+        txs_height: None,
+        txs_count: None,
+        txs_type: None,
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v.is_array());
+
+    let q = QueryType::Txs {
+        account: val_acct,
+        txs_height: None,
+        txs_count: Some(5),
+        txs_type: Some("0x1::not_a_module::not_a_function".to_string()),
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v.as_array().unwrap().is_empty());
+}
+
+/// the txs filter is a substring of the function id, and the count is capped
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn txs_history_substring_and_cap() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::Txs {
+        account: val_acct,
+        txs_height: None,
+        txs_count: Some(5_000),
+        txs_type: Some("ol_account".to_string()),
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    let list = v.as_array().unwrap();
+    assert!(list.len() <= 1_000);
+    assert!(list
+        .iter()
+        .all(|tx| tx["function"].as_str().unwrap().contains("ol_account")));
+}
+
+/// balances for a file of addresses, with one that is not on chain
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn balance_account_file_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let file = std::env::temp_dir().join("balance_account_file_test.txt");
+    std::fs::write(&file, format!("{}\n\n# not on chain\n0x1234\n", val_acct)).unwrap();
+
+    let q = QueryType::Balance {
+        account: None,
+        account_file: Some(file),
+        raw: false,
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    let list = v.as_array().unwrap();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list[0]["total"].as_f64().unwrap(), 1000.0);
+    assert_eq!(list[1]["not_found"], true);
+}
+
+/// an account which never rotated has its current key and no rotations
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn rotation_history_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::RotationHistory { account: val_acct };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v["current_auth_key"].is_string());
+    assert!(v["rotations"].as_array().unwrap().is_empty());
+}
+
+/// an account which never rotated has its address as the auth key
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn auth_key_not_rotated() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::AuthKey { account: val_acct };
+    let v = q.query_to_json(&c).await.unwrap();
+    let key: AccountAddress = v["auth_key"].as_str().unwrap().parse().unwrap();
+    assert_eq!(key, val_acct);
+    assert_eq!(v["rotated"], false);
+}
+
+/// batch balances from a file, with a null balance for a missing account
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn balance_batch_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let file = std::env::temp_dir().join("balance_batch_test.txt");
+    std::fs::write(&file, format!("{}\n0x1234\n", val_acct)).unwrap();
+
+    let q = QueryType::BalanceBatch {
+        path: file,
+        concurrency: None,
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    let list = v.as_array().unwrap();
+    assert_eq!(list.len(), 2);
+    let b: LibraBalanceDisplay = serde_json::from_value(list[0]["balance"].clone()).unwrap();
+    assert_eq!(b.total, 1000.0);
+    assert!(list[1]["balance"].is_null());
+    assert!(list[1]["error"].is_string());
+}
+
+/// slow wallet split of a validator, and null fields for a non slow wallet
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn slow_wallet_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::SlowWallet { account: val_acct };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert_eq!(v["total"].as_f64().unwrap(), 1000.0);
+    assert_eq!(
+        v["unlocked"].as_f64().unwrap() + v["locked"].as_f64().unwrap(),
+        1000.0
+    );
+
+    let q = QueryType::SlowWallet {
+        account: "0x1234".parse().unwrap(),
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v["unlocked"].is_null());
+    assert!(v["total"].is_null());
+}
+
+/// genesis validators vouch for each other
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn vouches_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::Vouches { account: val_acct };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v["received"].is_array());
+    assert!(v["given"].is_array());
+    for r in v["received"].as_array().unwrap() {
+        assert!(r["giver"].is_string());
+        assert!(r["epoch"].is_u64());
+    }
+}
+
+/// vouchers of a genesis validator and the given vouch limit, and empty
+/// lists for an account with no vouch state
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn vouch_limits_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::Vouch { account: val_acct };
+    let v = q.query_to_json(&c).await.unwrap();
+    let received = v["received_from"].as_array().unwrap();
+    assert_eq!(received.len() as u64, v["received_count"].as_u64().unwrap());
+    for r in received {
+        assert!(r["given_count"].as_u64().unwrap() <= v["limit"].as_u64().unwrap());
+    }
+
+    let q = QueryType::Vouch {
+        account: "0x1234".parse().unwrap(),
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v["received_from"].as_array().unwrap().is_empty());
+    assert_eq!(v["given_count"].as_u64().unwrap(), 0);
+}
+
+/// the genesis validator is in the universe and active
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn validator_universe_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::ValidatorUniverse {
+        offset: 0,
+        limit: None,
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    let vals = v["validators"].as_array().unwrap();
+    assert_eq!(vals.len() as u64, v["total"].as_u64().unwrap());
+    let me = vals
+        .iter()
+        .find(|r| r["address"] == val_acct.to_hex_literal())
+        .expect("validator not in universe");
+    assert_eq!(me["status"], "active");
+
+    let q = QueryType::ValidatorUniverse {
+        offset: 0,
+        limit: Some(0),
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v["validators"].as_array().unwrap().is_empty());
+}
+
+/// an account which never pledged has an empty list of pledges
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn pledge_empty_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::Pledge { account: val_acct };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert_eq!(v, serde_json::json!([]));
+}
+
+/// an account which never donated has no receipts
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn receipts_empty_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+
+    let c = s.client();
+
+    let q = QueryType::Receipts {
+        account: "0x1234".parse().unwrap(),
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert_eq!(v, serde_json::json!([]));
+}
+
+/// fees of an account, and of the whole network this epoch
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn fee_maker_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+
+    let c = s.client();
+
+    let q = QueryType::FeeMaker {
+        account: Some("0x1234".parse().unwrap()),
+        all: false,
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert_eq!(v["epoch_fees_made"].as_u64().unwrap(), 0);
+    assert_eq!(v["lifetime_fees_made"].as_u64().unwrap(), 0);
+
+    let q = QueryType::FeeMaker {
+        account: None,
+        all: true,
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v["epoch_fees_made"].is_u64());
+    assert!(v["fee_makers"].is_u64());
+}
+
+/// the match index ratios are shares of the whole
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn match_index_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+
+    let c = s.client();
+
+    let v = QueryType::MatchIndex.query_to_json(&c).await.unwrap();
+    let wallets = v["wallets"].as_array().unwrap();
+    let total: f64 = wallets.iter().map(|w| w["ratio"].as_f64().unwrap()).sum();
+    assert!(total == 0.0 || (total - 1.0).abs() < 0.001);
+}
+
+/// an account without an ancestry tree is reported as not found
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn ancestry_not_found() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+
+    let c = s.client();
+
+    let q = QueryType::Ancestry {
+        account: "0x1234".parse().unwrap(),
+    };
+    let err = q.query_to_json(&c).await.unwrap_err();
+    assert_eq!(err.exit(), QueryExit::NotFound);
+}
+
+/// proof-of-fee bid of a genesis validator, and the auction state
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn pof_bid_and_auction_test() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+    let val_acct = s.first_account.address();
+
+    let c = s.client();
+
+    let q = QueryType::PofBid {
+        account: Some(val_acct),
+        all: false,
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v["bid"].is_u64());
+    assert!(v["expiration_epoch"].is_u64());
+    assert_eq!(v["is_valid"], v["bid"].as_u64().unwrap() > 0);
+    assert!(v["is_active"].is_boolean());
+
+    let q = QueryType::PofBid {
+        account: None,
+        all: true,
+    };
+    let v = q.query_to_json(&c).await.unwrap();
+    let bids: Vec<u64> = v
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b["bid"].as_u64().unwrap())
+        .collect();
+    assert!(bids.windows(2).all(|w| w[0] >= w[1]));
+
+    let q = QueryType::PofAuction;
+    let v = q.query_to_json(&c).await.unwrap();
+    assert!(v["clearing_bid"].is_u64());
+    assert!(v["seats_offered"].is_u64());
+}
@@ -0,0 +1,60 @@
+use libra_query::{
+    query_cli::client_from_url,
+    query_error::{QueryError, QueryExit},
+    query_type::QueryType,
+};
+use libra_smoke_tests::libra_smoke::LibraSmoke;
+use libra_types::exports::Client;
+
+/// a missing account exits with the not-found code
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn exit_code_not_found() {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+
+    let c = s.client();
+
+    let q = QueryType::Resource {
+        account: "0x1234".parse().unwrap(),
+        resource_path_string: Some("0x1::slow_wallet::SlowWallet".to_string()),
+        all: false,
+    };
+    let err = q.query_to_json(&c).await.unwrap_err();
+    assert!(matches!(err, QueryError::ResourceNotFound(_)));
+    let exit = err.exit();
+    assert_eq!(exit, QueryExit::NotFound);
+    assert_eq!(exit.code(), 3);
+}
+
+/// a node which cannot be reached exits with the connection code
+#[tokio::test]
+async fn exit_code_connection() {
+    // nothing should be listening on this port
+    let c = Client::new("http://127.0.0.1:1".parse().unwrap());
+
+    let q = QueryType::BlockHeight;
+    let err = q.query_to_json(&c).await.unwrap_err();
+    assert!(matches!(err, QueryError::NodeUnreachable(_)));
+
+    // the same code once the error has passed through anyhow
+    let exit = QueryExit::from_error(&anyhow::Error::from(err));
+    assert_eq!(exit, QueryExit::Connection);
+    assert_eq!(exit.code(), 2);
+}
+
+/// a --url which is not a node is rejected before querying
+#[tokio::test]
+async fn client_from_bad_url() {
+    let err = client_from_url(&"ftp://127.0.0.1:8080".parse().unwrap())
+        .await
+        .unwrap_err();
+    assert_eq!(err.exit(), QueryExit::BadInput);
+
+    // nothing should be listening on this port
+    let err = client_from_url(&"http://127.0.0.1:1".parse().unwrap())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, QueryError::NodeUnreachable(_)));
+    assert!(err.to_string().contains("no node reachable at"));
+}
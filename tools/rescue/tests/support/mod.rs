@@ -167,6 +167,7 @@ pub async fn upgrade_multiple_impl(
         tx_profile: None,
         tx_cost: Some(TxCost::prod_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
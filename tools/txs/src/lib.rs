@@ -4,6 +4,7 @@ pub mod publish;
 pub mod stream;
 pub mod submit_transaction;
 pub mod transfer;
+pub mod tx_signer;
 pub mod txs_cli;
 pub mod txs_cli_community;
 pub mod txs_cli_governance;
@@ -1,10 +1,14 @@
-use anyhow::Result;
 use clap::Parser;
 use diem_logger::{Level, Logger};
+use libra_query::query_error::report_error;
 use libra_txs::txs_cli::TxsCli;
+use std::process::exit;
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     Logger::new().level(Level::Info).init();
-    TxsCli::parse().run().await
+    let cli = TxsCli::parse();
+    if let Err(e) = cli.run().await {
+        exit(report_error(&e, cli.error_json));
+    }
 }
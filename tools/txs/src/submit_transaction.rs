@@ -1,6 +1,9 @@
 //! Module for managing transaction sending and management with Diem blockchain.
 
-use crate::txs_cli::to_legacy_address;
+use crate::{
+    tx_signer::{self, sign_raw_transaction},
+    txs_cli::to_legacy_address,
+};
 use anyhow::{bail, Context};
 use diem::common::types::{CliConfig, ConfigSearchMode};
 use diem_logger::prelude::*;
@@ -39,6 +42,8 @@ pub struct Sender {
     client: Client,
     chain_id: ChainId,
     pub response: Option<TransactionOnChainData>,
+    /// optional external signer, e.g. a hardware token
+    signer: Option<Box<dyn tx_signer::TxSigner>>,
 }
 
 impl Sender {
@@ -76,6 +81,7 @@ impl Sender {
             local_account,
             chain_id,
             response: None,
+            signer: None,
         })
     }
 
@@ -84,6 +90,33 @@ impl Sender {
         cost.clone_into(&mut self.tx_cost);
     }
 
+    /// Sign with an external backend (e.g. a hardware token) instead of the
+    /// key held by `local_account`. The address and sequence number are still
+    /// tracked by `local_account`. Fails if the backend's key is not the
+    /// account's current authentication key, since the chain would reject
+    /// every transaction it signs.
+    pub async fn set_signer(&mut self, signer: Box<dyn tx_signer::TxSigner>) -> anyhow::Result<()> {
+        let address = self.local_account.address();
+        let on_chain = self
+            .client
+            .get_account(address)
+            .await
+            .context("cannot read the account's authentication key")?
+            .into_inner()
+            .authentication_key;
+        let signer_auth = AuthenticationKey::ed25519(&signer.public_key());
+        if signer_auth != on_chain {
+            bail!(
+                "the signer's key {} does not match the authentication key {} of account {}",
+                signer_auth,
+                on_chain,
+                address
+            );
+        }
+        self.signer = Some(signer);
+        Ok(())
+    }
+
     /// load from local app configs
     pub async fn from_app_cfg(app_cfg: &AppCfg, profile: Option<String>) -> anyhow::Result<Self> {
         let profile = app_cfg.get_profile(profile)?;
@@ -129,6 +162,7 @@ impl Sender {
             local_account,
             chain_id,
             response: None,
+            signer: None,
         };
 
         Ok(s)
@@ -185,6 +219,7 @@ impl Sender {
                 local_account,
                 chain_id,
                 response: None,
+                signer: None,
             };
             return Ok(s);
         }
@@ -205,7 +240,7 @@ impl Sender {
             info!("script code hash: {}", &hash.to_hex_literal());
         }
 
-        let signed = self.sign_payload(payload);
+        let signed = self.sign_payload(payload)?;
         let spin = OLProgress::spin_steady(500, "awaiting transaction response".to_string());
         println!("sending transaction...");
         let r = self.submit(&signed).await?;
@@ -232,7 +267,10 @@ impl Sender {
     }
 
     /// Signs a transaction payload.
-    pub fn sign_payload(&mut self, payload: TransactionPayload) -> SignedTransaction {
+    pub fn sign_payload(
+        &mut self,
+        payload: TransactionPayload,
+    ) -> anyhow::Result<SignedTransaction> {
         let t = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -243,7 +281,17 @@ impl Sender {
             .gas_unit_price(self.tx_cost.coin_price_per_unit)
             .max_gas_amount(self.tx_cost.max_gas_unit_for_tx);
 
-        self.local_account.sign_with_transaction_builder(tb)
+        let Some(signer) = &self.signer else {
+            return Ok(self.local_account.sign_with_transaction_builder(tb));
+        };
+
+        let raw = tb
+            .sender(self.local_account.address())
+            .sequence_number(self.local_account.sequence_number())
+            .build();
+        let signed = sign_raw_transaction(signer.as_ref(), raw)?;
+        *self.local_account.sequence_number_mut() += 1;
+        Ok(signed)
     }
 
     /// submit to API and wait for the transaction on chain data
@@ -284,7 +332,7 @@ impl Sender {
         &mut self,
         payload: TransactionPayload,
     ) -> anyhow::Result<Vec<UserTransaction>> {
-        let signed = self.sign_payload(payload);
+        let signed = self.sign_payload(payload)?;
 
         let res = self
             .client
@@ -0,0 +1,50 @@
+//! Signing backends for the Sender.
+
+use diem_sdk::{
+    crypto::{
+        ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+        traits::signing_message,
+        PrivateKey, SigningKey,
+    },
+    types::transaction::{RawTransaction, SignedTransaction},
+};
+
+/// Something which can produce ed25519 signatures for a transaction.
+///
+/// By default the Sender signs with the software key derived from the
+/// mnemonic (see `KeyFactory`). For high value accounts the key can instead
+/// live on an external device, such as a PKCS#11 token or a YubiKey, which
+/// implements this trait so that the private key never leaves the device.
+///
+/// The message handed to `sign_message` is already the Diem signing
+/// message: the domain separation hash of `RawTransaction` followed by the
+/// BCS bytes of the transaction. Implementations should sign those bytes
+/// as-is with plain ed25519.
+pub trait TxSigner: Send + Sync {
+    /// The public key which verifies the signatures produced.
+    fn public_key(&self) -> Ed25519PublicKey;
+
+    /// Sign the prepared signing message.
+    fn sign_message(&self, message: &[u8]) -> anyhow::Result<Ed25519Signature>;
+}
+
+/// The default software backend: an in-memory private key.
+impl TxSigner for Ed25519PrivateKey {
+    fn public_key(&self) -> Ed25519PublicKey {
+        PrivateKey::public_key(self)
+    }
+
+    fn sign_message(&self, message: &[u8]) -> anyhow::Result<Ed25519Signature> {
+        Ok(self.sign_arbitrary_message(message))
+    }
+}
+
+/// Sign a raw transaction with any signing backend.
+pub fn sign_raw_transaction(
+    signer: &dyn TxSigner,
+    raw: RawTransaction,
+) -> anyhow::Result<SignedTransaction> {
+    let message = signing_message(&raw);
+    let signature = signer.sign_message(&message)?;
+    Ok(SignedTransaction::new(raw, signer.public_key(), signature))
+}
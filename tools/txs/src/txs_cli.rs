@@ -66,6 +66,10 @@ pub struct TxsCli {
     /// optional, use legacy (v5) 16-byte address format for a sender
     #[clap(long)]
     pub legacy_address: bool,
+
+    /// optional, on failure print the error kind and message as json to stdout
+    #[clap(long)]
+    pub error_json: bool,
 }
 
 #[derive(clap::Subcommand)]
@@ -1,6 +1,7 @@
 //! Validator subcommands
 
 use crate::submit_transaction::Sender;
+use anyhow::bail;
 use diem_types::account_address::AccountAddress;
 use libra_cached_packages::libra_stdlib;
 use libra_query::{account_queries, query_view};
@@ -27,6 +28,8 @@ pub enum CommunityTxs {
     Batch(BatchTx),
     /// Donors to Donor Voice addresses can vote to reject transactions
     Veto(VetoTx),
+    /// Donors can propose and vote to liquidate a Donor Voice address
+    Liquidate(LiquidateTx),
     /// Migrate legacy account to initialize offer structure
     Migration(MigrateOfferTx), // TODO remove after migration complete
     /// Initilize legacy multi-sig account governance
@@ -64,23 +67,48 @@ impl CommunityTxs {
                 }
             },
             CommunityTxs::GovAdmin(admin) => match admin.run(sender).await {
-                Ok(_) => println!("SUCCESS: community wallet admin proposed"),
+                Ok(Some(id)) => println!(
+                    "SUCCESS: community wallet admin proposed, proposal id: {}",
+                    id
+                ),
+                Ok(None) => println!(
+                    "SUCCESS: the same admin change was already pending, your vote was added to it"
+                ),
                 Err(e) => {
                     println!("ERROR: could not propose new admin, message: {}", e);
                 }
             },
             CommunityTxs::Propose(propose) => match propose.run(sender).await {
-                Ok(_) => println!("SUCCESS: community wallet transfer proposed"),
+                Ok(id) => {
+                    println!("SUCCESS: community wallet transfer proposed");
+                    if let Some(id) = id {
+                        println!("proposal id: {}", id);
+                    }
+                    if let Some(hash) = sender.tx_hash() {
+                        println!("transaction hash: {}", hash.to_hex_literal());
+                    }
+                }
                 Err(e) => {
                     println!("ERROR: community wallet transfer rejected, message: {}", e);
                 }
             },
             CommunityTxs::Veto(veto) => match veto.run(sender).await {
-                Ok(_) => println!("SUCCESS: veto vote submitted"),
+                Ok(tally) => println!("SUCCESS: veto vote submitted, tally: {}", tally),
                 Err(e) => {
                     println!("ERROR: veto vote rejected, message: {}", e);
                 }
             },
+            CommunityTxs::Liquidate(liquidate) => match liquidate.run(sender).await {
+                Ok(frozen) => {
+                    println!("SUCCESS: liquidation vote submitted");
+                    if frozen == Some(true) {
+                        println!("the wallet is frozen and queued for liquidation");
+                    }
+                }
+                Err(e) => {
+                    println!("ERROR: liquidation vote rejected, message: {}", e);
+                }
+            },
             CommunityTxs::Batch(batch) => match batch.run(sender).await {
                 Ok(_) => {}
                 Err(e) => {
@@ -201,7 +229,7 @@ impl CageTx {
 #[derive(clap::Args)]
 pub struct AdminTx {
     #[clap(short, long)]
-    /// The SlowWallet recipient of funds
+    /// The Community Wallet whose authorities change
     pub community_wallet: AccountAddress,
     #[clap(short, long)]
     /// Admin to add (or remove) from the multisig
@@ -218,10 +246,19 @@ pub struct AdminTx {
 }
 
 impl AdminTx {
-    pub async fn run(&self, sender: &mut Sender) -> anyhow::Result<()> {
+    /// Propose the authority change, and return the id of the new proposal.
+    /// Proposing a change which is already pending votes for it instead, and
+    /// returns None.
+    pub async fn run(&self, sender: &mut Sender) -> anyhow::Result<Option<u64>> {
         // Default to adding a signer if the `drop` flag is not provided
         let is_add_operation = self.drop.unwrap_or(true);
 
+        let next_id = account_queries::get_next_multi_auth_proposal_id(
+            sender.client(),
+            self.community_wallet,
+        )
+        .await?;
+
         let payload = libra_stdlib::community_wallet_init_change_signer_community_multisig(
             self.community_wallet,
             self.admin,
@@ -230,7 +267,14 @@ impl AdminTx {
             self.epochs.unwrap_or(10), // todo: remo
         );
         sender.sign_submit_wait(payload).await?;
-        Ok(())
+
+        // a new proposal takes a guid from the multisig account
+        let after = account_queries::get_next_multi_auth_proposal_id(
+            sender.client(),
+            self.community_wallet,
+        )
+        .await?;
+        Ok((after > next_id).then_some(next_id))
     }
 }
 
@@ -251,7 +295,20 @@ pub struct ProposeTx {
 }
 
 impl ProposeTx {
-    pub async fn run(&self, sender: &mut Sender) -> anyhow::Result<()> {
+    /// Propose the payment, and return the id of the new proposal. An
+    /// identical payment which is already pending is voted for instead, and
+    /// returns None.
+    pub async fn run(&self, sender: &mut Sender) -> anyhow::Result<Option<u64>> {
+        if self.amount == 0 {
+            bail!("the amount to transfer must be greater than 0");
+        }
+
+        let next_id = account_queries::get_next_multi_auth_proposal_id(
+            sender.client(),
+            self.community_wallet,
+        )
+        .await?;
+
         let payload = libra_stdlib::donor_voice_txs_propose_payment_tx(
             self.community_wallet,
             self.recipient,
@@ -259,7 +316,14 @@ impl ProposeTx {
             self.description.clone().into_bytes(),
         );
         sender.sign_submit_wait(payload).await?;
-        Ok(())
+
+        // a new proposal takes a guid from the multisig account
+        let after = account_queries::get_next_multi_auth_proposal_id(
+            sender.client(),
+            self.community_wallet,
+        )
+        .await?;
+        Ok((after > next_id).then_some(next_id))
     }
 }
 
@@ -488,11 +552,74 @@ pub struct VetoTx {
 }
 
 impl VetoTx {
-    pub async fn run(&self, sender: &mut Sender) -> anyhow::Result<()> {
+    /// Submit the veto vote, and return the updated veto tally.
+    pub async fn run(&self, sender: &mut Sender) -> anyhow::Result<serde_json::Value> {
+        // don't submit a veto which will be rejected on chain
+        if !account_queries::is_donor_voice_tx_scheduled(
+            sender.client(),
+            self.community_wallet,
+            self.proposal_id,
+        )
+        .await?
+        {
+            bail!(
+                "no scheduled transaction with proposal id {} at community wallet {}",
+                self.proposal_id,
+                self.community_wallet
+            );
+        }
+
         let payload =
             libra_stdlib::donor_voice_txs_propose_veto_tx(self.community_wallet, self.proposal_id);
         sender.sign_submit_wait(payload).await?;
-        Ok(())
+
+        account_queries::get_veto_tally(sender.client(), self.community_wallet, self.proposal_id)
+            .await
+    }
+}
+
+#[derive(clap::Args)]
+pub struct LiquidateTx {
+    #[clap(short, long)]
+    /// The Donor Voice wallet to liquidate
+    pub community_wallet: AccountAddress,
+}
+
+impl LiquidateTx {
+    /// Propose the liquidation if none is pending, then vote for it.
+    /// Returns true once enough donors voted and the wallet is frozen,
+    /// waiting to be liquidated at the epoch boundary, or None if the state
+    /// could not be read after voting.
+    pub async fn run(&self, sender: &mut Sender) -> anyhow::Result<Option<bool>> {
+        // a frozen wallet is already queued for liquidation
+        if account_queries::is_donor_voice_frozen(sender.client(), self.community_wallet).await? {
+            bail!(
+                "community wallet {} is already frozen and queued for liquidation",
+                self.community_wallet
+            );
+        }
+
+        if !account_queries::is_liquidation_proposed(sender.client(), self.community_wallet).await?
+        {
+            let payload = libra_stdlib::donor_voice_txs_propose_liquidate_tx(self.community_wallet);
+            sender.sign_submit_wait(payload).await?;
+        }
+
+        let payload = libra_stdlib::donor_voice_txs_vote_liquidation_tx(self.community_wallet);
+        sender.sign_submit_wait(payload).await?;
+
+        // the vote is on chain, so failing to read the result must not
+        // fail the command, or a script would retry and vote again
+        match account_queries::is_donor_voice_frozen(sender.client(), self.community_wallet).await {
+            Ok(frozen) => Ok(Some(frozen)),
+            Err(e) => {
+                println!(
+                    "WARN: liquidation vote submitted, but could not read the wallet state: {:#}",
+                    e
+                );
+                Ok(None)
+            }
+        }
     }
 }
 
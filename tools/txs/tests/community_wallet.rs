@@ -37,6 +37,7 @@ async fn migrate_community_wallet_with_flag() {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     cli_set_community_wallet.run()
@@ -78,6 +79,7 @@ async fn migrate_community_wallet() {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     cli_set_community_wallet.run()
@@ -116,6 +118,7 @@ async fn create_community_wallet_with_flag() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     cli_set_community_wallet.run()
@@ -175,6 +178,7 @@ async fn new_community_wallet_cant_transfer() -> Result<(), anyhow::Error> {
             tx_profile: None,
             tx_cost: Some(TxCost::default_baseline_cost()),
             estimate_only: false,
+            error_json: false,
         };
 
         // Execute the transfer
@@ -212,6 +216,7 @@ async fn new_community_wallet_cant_transfer() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     cli_transfer.run()
@@ -239,6 +244,7 @@ async fn new_community_wallet_cant_transfer() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     cli_set_community_wallet.run()
@@ -266,6 +272,7 @@ async fn new_community_wallet_cant_transfer() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     match transfer_cli.run().await {
@@ -676,6 +683,7 @@ async fn community_wallet_payment() -> Result<(), anyhow::Error> {
             tx_profile: None,
             tx_cost: Some(TxCost::default_baseline_cost()),
             estimate_only: false,
+            error_json: false,
         };
 
         // Execute funds transfer to the account
@@ -711,6 +719,7 @@ async fn community_wallet_payment() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     // Execute the transfer to the new admin account
@@ -739,6 +748,7 @@ async fn community_wallet_payment() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     // Execute community wallet creation
@@ -763,6 +773,7 @@ async fn community_wallet_payment() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
     cli_finalize_cage.run()
         .await
@@ -791,7 +802,9 @@ async fn community_wallet_payment() -> Result<(), anyhow::Error> {
         url: Some(s.api_endpoint.clone()),
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
-        estimate_only: false,    };
+        estimate_only: false,
+        error_json: false,
+    };
 
     // Execute the payment proposal
     cli_propose_payment.run()
@@ -869,6 +882,7 @@ async fn add_community_wallet_admin() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
@@ -929,6 +943,7 @@ async fn add_community_wallet_admin() -> Result<(), anyhow::Error> {
             tx_profile: None,
             tx_cost: Some(TxCost::default_baseline_cost()),
             estimate_only: false,
+            error_json: false,
             legacy_address: false,
         };
 
@@ -1107,6 +1122,7 @@ async fn remove_community_wallet_admin() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
@@ -1168,6 +1184,7 @@ async fn remove_community_wallet_admin() -> Result<(), anyhow::Error> {
             tx_profile: None,
             tx_cost: Some(TxCost::default_baseline_cost()),
             estimate_only: false,
+            error_json: false,
             legacy_address: false,
         };
 
@@ -1284,6 +1301,7 @@ async fn cancel_community_wallet_payment() -> Result<(), anyhow::Error> {
             tx_profile: None,
             tx_cost: Some(TxCost::default_baseline_cost()),
             estimate_only: false,
+            error_json: false,
         };
 
         // Execute the transfer
@@ -1320,6 +1338,7 @@ async fn cancel_community_wallet_payment() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     cli_transfer.run()
@@ -1347,6 +1366,7 @@ async fn cancel_community_wallet_payment() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     cli_set_community_wallet.run()
@@ -1371,6 +1391,7 @@ async fn cancel_community_wallet_payment() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     cli_finalize_cage.run()
@@ -1398,6 +1419,7 @@ async fn cancel_community_wallet_payment() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     cli_propose_payment.run()
@@ -1428,6 +1450,7 @@ async fn cancel_community_wallet_payment() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     cli_propose_payment_signer_two.run()
@@ -1469,6 +1492,7 @@ async fn cancel_community_wallet_payment() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     // Execute the transfer
@@ -1493,6 +1517,7 @@ async fn cancel_community_wallet_payment() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
     };
 
     // Execute the VetoTx command
@@ -1549,6 +1574,7 @@ async fn setup_environment() -> (LibraSmoke, TempPath, AccountAddress, String, A
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
@@ -1600,6 +1626,7 @@ async fn run_cli_transfer(
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
@@ -1631,6 +1658,7 @@ async fn run_cli_community_init(
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
@@ -1659,6 +1687,7 @@ async fn run_cli_claim_offer(
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
@@ -1686,6 +1715,7 @@ async fn run_cli_community_cage(
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
@@ -1715,6 +1745,7 @@ async fn run_cli_community_propose_offer(
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
@@ -1805,6 +1836,7 @@ async fn test_offer_migration() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
@@ -1841,6 +1873,7 @@ async fn test_offer_migration() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
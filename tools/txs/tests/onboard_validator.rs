@@ -51,6 +51,7 @@ async fn smoke_onboard_validator() -> anyhow::Result<()> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
@@ -79,6 +80,7 @@ async fn smoke_onboard_validator() -> anyhow::Result<()> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
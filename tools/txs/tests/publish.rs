@@ -41,6 +41,7 @@ async fn smoke_publish() {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
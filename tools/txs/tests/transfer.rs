@@ -42,6 +42,7 @@ async fn smoke_transfer_existing_account() {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
@@ -82,6 +83,7 @@ async fn smoke_transfer_create_account() -> Result<(), anyhow::Error> {
         tx_profile: None,
         tx_cost: Some(TxCost::default_baseline_cost()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 
@@ -126,6 +128,7 @@ async fn smoke_transfer_estimate() {
         tx_cost: Some(TxCost::default_cheap_txs_cost()),
         estimate_only: true, // THIS IS THE TEST
         legacy_address: false,
+        error_json: false,
     };
 
     cli.run().await.expect("could not get estimate");
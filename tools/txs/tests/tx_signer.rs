@@ -0,0 +1,100 @@
+use diem_sdk::{
+    crypto::{
+        ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+        PrivateKey, SigningKey,
+    },
+    types::{
+        account_address::AccountAddress,
+        chain_id::ChainId,
+        transaction::{RawTransaction, TransactionAuthenticator},
+    },
+};
+use libra_cached_packages::libra_stdlib;
+use libra_smoke_tests::libra_smoke::LibraSmoke;
+use libra_txs::{
+    submit_transaction::Sender,
+    tx_signer::{sign_raw_transaction, TxSigner},
+};
+use std::sync::{Arc, Mutex};
+
+/// Stands in for a hardware token: the key stays inside the "device" and
+/// the host only sees the public key and the signatures.
+struct MockHardwareSigner {
+    device_key: Ed25519PrivateKey,
+    signed_count: Arc<Mutex<u64>>,
+}
+
+impl TxSigner for MockHardwareSigner {
+    fn public_key(&self) -> Ed25519PublicKey {
+        PrivateKey::public_key(&self.device_key)
+    }
+
+    fn sign_message(&self, message: &[u8]) -> anyhow::Result<Ed25519Signature> {
+        *self.signed_count.lock().unwrap() += 1;
+        Ok(self.device_key.sign_arbitrary_message(message))
+    }
+}
+
+#[test]
+fn mock_hardware_signature_verifies() {
+    let signer = MockHardwareSigner {
+        device_key: Ed25519PrivateKey::try_from([7u8; 32].as_slice()).unwrap(),
+        signed_count: Arc::new(Mutex::new(0)),
+    };
+
+    let raw = RawTransaction::new(
+        AccountAddress::random(),
+        0,
+        libra_stdlib::ol_account_transfer(AccountAddress::random(), 100),
+        1_000,
+        100,
+        0,
+        ChainId::test(),
+    );
+
+    let signed = sign_raw_transaction(&signer, raw).unwrap();
+    assert_eq!(*signer.signed_count.lock().unwrap(), 1);
+
+    match signed.authenticator() {
+        TransactionAuthenticator::Ed25519 { public_key, .. } => {
+            assert_eq!(public_key, signer.public_key())
+        }
+        _ => panic!("expected a single ed25519 authenticator"),
+    }
+
+    assert!(signed.check_signature().is_ok());
+}
+
+/// The Sender only takes a backend holding the account's key, and then
+/// signs its transactions with it.
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn sender_signs_with_mock_hardware() -> anyhow::Result<()> {
+    let mut ls = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start libra smoke");
+    let val_app_cfg = ls.first_account_app_cfg()?;
+    let mut sender = Sender::from_app_cfg(&val_app_cfg, None).await?;
+
+    // a device holding some other key is refused
+    let wrong = MockHardwareSigner {
+        device_key: Ed25519PrivateKey::try_from([7u8; 32].as_slice()).unwrap(),
+        signed_count: Arc::new(Mutex::new(0)),
+    };
+    assert!(sender.set_signer(Box::new(wrong)).await.is_err());
+
+    let signed_count = Arc::new(Mutex::new(0));
+    let device = MockHardwareSigner {
+        device_key: ls.first_account.private_key().to_owned(),
+        signed_count: signed_count.clone(),
+    };
+    sender.set_signer(Box::new(device)).await?;
+
+    let res = sender
+        .transfer(AccountAddress::random(), 10.0, false)
+        .await?
+        .unwrap();
+    assert!(res.info.status().is_success());
+    assert_eq!(*signed_count.lock().unwrap(), 1);
+
+    Ok(())
+}
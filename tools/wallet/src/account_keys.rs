@@ -4,8 +4,11 @@ use crate::{
     key_gen::keygen,
     load_keys,
 };
-use anyhow::Result;
-use diem_crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
+use anyhow::{bail, Context, Result};
+use diem_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+    PrivateKey,
+};
 use diem_types::{account_address::AccountAddress, transaction::authenticator::AuthenticationKey};
 use serde::Serialize;
 use std::{path::Path, str::FromStr};
@@ -97,13 +100,28 @@ pub fn legacy_keygen(danger_print: bool) -> Result<KeyChain> {
 
 /// Get the legacy keys from the wallet
 pub fn get_keys_from_prompt() -> Result<KeyChain> {
-    let (_auth_key, _account, wallet) = load_keys::get_account_from_prompt();
+    get_keys_from_prompt_with_passphrase(None)
+}
+
+/// Get the legacy keys from the wallet, derived with the passphrase if given
+pub fn get_keys_from_prompt_with_passphrase(passphrase: Option<&str>) -> Result<KeyChain> {
+    let (_auth_key, _account, wallet) =
+        load_keys::get_account_from_prompt_with_passphrase(passphrase);
     KeyChain::new(&wallet)
 }
 
 /// for libs to get the keys from a mnemonic
 pub fn get_keys_from_mnem(mnem: String) -> Result<KeyChain> {
-    let (_auth_key, _account, wallet) = load_keys::get_account_from_mnem(mnem)?;
+    get_keys_from_mnem_with_passphrase(mnem, None)
+}
+
+/// for libs to get the keys from a mnemonic, and the passphrase it was created with
+pub fn get_keys_from_mnem_with_passphrase(
+    mnem: String,
+    passphrase: Option<&str>,
+) -> Result<KeyChain> {
+    let (_auth_key, _account, wallet) =
+        load_keys::get_account_from_mnem_with_passphrase(mnem, passphrase)?;
     KeyChain::new(&wallet)
 }
 
@@ -119,6 +137,29 @@ pub fn get_account_from_private(pri_key: &Ed25519PrivateKey) -> AccountKeys {
     }
 }
 
+/// For users who only have a hex private key and no mnemonic, get the public
+/// key, account address, and authentication key it controls.
+pub fn account_from_private_key_hex(
+    private_key_hex: &str,
+) -> Result<(Ed25519PublicKey, AccountAddress, AuthenticationKey)> {
+    let trimmed = private_key_hex.trim();
+    let bytes = hex::decode(trimmed.strip_prefix("0x").unwrap_or(trimmed))
+        .context("private key is not a valid hex string")?;
+    if bytes.len() != Ed25519PrivateKey::LENGTH {
+        bail!(
+            "private key should be {} bytes ({} hex characters), got {} bytes",
+            Ed25519PrivateKey::LENGTH,
+            Ed25519PrivateKey::LENGTH * 2,
+            bytes.len()
+        );
+    }
+    let pri_key = Ed25519PrivateKey::try_from(bytes.as_slice())
+        .context("private key is not a valid ed25519 key")?;
+
+    let keys = get_account_from_private(&pri_key);
+    Ok((pri_key.public_key(), keys.account, keys.auth_key))
+}
+
 fn get_account_from_nth(w: &WalletLibrary, n: u8) -> Result<AccountKeys> {
     let pri_keys = LegacyKeyScheme::new(w);
 
@@ -193,6 +234,47 @@ fn test_legacy_keys() {
     );
 }
 
+#[test]
+fn test_account_from_private_key_hex() {
+    let alice_mnem = "talent sunset lizard pill fame nuclear spy noodle basket okay critic grow sleep legend hurry pitch blanket clerk impose rough degree sock insane purse";
+    let l = get_keys_from_mnem(alice_mnem.to_string()).unwrap();
+    let pri_hex = hex::encode(l.child_0_owner.pri_key.to_bytes());
+
+    let (public_key, account, auth_key) = account_from_private_key_hex(&pri_hex).unwrap();
+    assert_eq!(public_key, l.child_0_owner.pri_key.public_key());
+    assert_eq!(account, l.child_0_owner.account);
+    assert_eq!(auth_key, l.child_0_owner.auth_key);
+
+    // a 0x prefix is accepted
+    let (_, prefixed, _) = account_from_private_key_hex(&format!("0x{pri_hex}")).unwrap();
+    assert_eq!(prefixed, account);
+
+    // malformed input is an error
+    let err = account_from_private_key_hex("not hex").unwrap_err();
+    assert!(err.to_string().contains("not a valid hex"));
+    let err = account_from_private_key_hex(&pri_hex[..62]).unwrap_err();
+    assert!(err.to_string().contains("should be 32 bytes"));
+}
+
+#[test]
+fn test_keys_with_passphrase() {
+    let alice_mnem = "talent sunset lizard pill fame nuclear spy noodle basket okay critic grow sleep legend hurry pitch blanket clerk impose rough degree sock insane purse";
+
+    // no passphrase is the same as before
+    let l = get_keys_from_mnem_with_passphrase(alice_mnem.to_string(), None).unwrap();
+    assert_eq!(
+        l.child_0_owner.account.to_string(),
+        "87515d94a244235a1433d7117bc0cb154c613c2f4b1e67ca8d98a542ee3f59f5"
+    );
+
+    // a passphrase gives another account, the same one each time
+    let p = get_keys_from_mnem_with_passphrase(alice_mnem.to_string(), Some("hunter2")).unwrap();
+    assert_ne!(p.child_0_owner.account, l.child_0_owner.account);
+    let p_again =
+        get_keys_from_mnem_with_passphrase(alice_mnem.to_string(), Some("hunter2")).unwrap();
+    assert_eq!(p.child_0_owner.account, p_again.child_0_owner.account);
+}
+
 #[test]
 // We want to check that the address and auth key derivation is the same from what Diem generates, and what the vendor types do.
 fn type_conversion_give_same_auth_and_address() {
@@ -25,8 +25,10 @@ use std::ops::AddAssign;
 
 use diem_crypto::{
     compat::Sha3_256,
-    ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    hash::CryptoHash,
     hkdf::Hkdf,
+    traits::SigningKey,
 };
 use diem_types::{account_address::AccountAddress, transaction::authenticator::AuthenticationKey};
 
@@ -80,10 +82,19 @@ impl std::convert::AsMut<u64> for ChildNumber {
     }
 }
 
+/// Where a derived key sits in the key tree of its Main key.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum KeyPath {
+    /// A key derived with `KeyFactory::private_child`
+    Child(ChildNumber),
+    /// A key derived with `KeyFactory::derive_account`
+    Account { account_index: u64, key_index: u64 },
+}
+
 /// Derived private key.
 pub struct ExtendedPrivKey {
-    /// Child number of the key used to derive from Parent.
-    _child_number: ChildNumber,
+    /// Path of the key used to derive from Parent.
+    key_path: KeyPath,
     /// Private key.
     private_key: Ed25519PrivateKey,
 }
@@ -92,13 +103,18 @@ impl ExtendedPrivKey {
     /// Constructor for creating an ExtendedPrivKey from a ed25519 PrivateKey. Note that the
     /// ChildNumber are not used in this iteration of DiemWallet, but in order to
     /// enable more general Hierarchical KeyDerivation schemes, we include it for completeness.
-    pub fn new(_child_number: ChildNumber, private_key: Ed25519PrivateKey) -> Self {
+    pub fn new(child_number: ChildNumber, private_key: Ed25519PrivateKey) -> Self {
         Self {
-            _child_number,
+            key_path: KeyPath::Child(child_number),
             private_key,
         }
     }
 
+    /// How this key was derived from the Main key
+    pub fn key_path(&self) -> KeyPath {
+        self.key_path
+    }
+
     /// Returns the PublicKey associated to a particular ExtendedPrivKey
     pub fn get_public(&self) -> Ed25519PublicKey {
         (&self.private_key).into()
@@ -109,15 +125,15 @@ impl ExtendedPrivKey {
         AuthenticationKey::ed25519(&self.get_public()).derived_address()
     }
 
-    // //////// 0L ////////
-    // // addresses in the 0L chains before V7 had a truncated address of 16 bytes
-    // pub fn get_ol_legacy_address(&self) -> AccountAddress {
-    //   // keep only last 16 bytes
-    //   let addr = &self.get_address();
-    //   let mut array = [0u8; 32]; // fill the buffer with 0 to prefix the address
-    //   array.copy_from_slice(&addr.as_slice()[16..]);
-    //   AccountAddress::new(array)
-    // }
+    //////// 0L ////////
+    /// addresses in the 0L chains before V7 had a truncated address of 16 bytes
+    pub fn get_ol_legacy_address(&self) -> AccountAddress {
+        // keep only last 16 bytes
+        let addr = self.get_address();
+        let mut array = [0u8; AccountAddress::LENGTH]; // fill the buffer with 0 to prefix the address
+        array[16..].copy_from_slice(&addr[16..]);
+        AccountAddress::new(array)
+    }
 
     /// Get private key
     pub fn get_private_key(&self) -> Ed25519PrivateKey {
@@ -129,20 +145,28 @@ impl ExtendedPrivKey {
         AuthenticationKey::ed25519(&self.get_public())
     }
 
-    // /// Diem specific sign function that is capable of signing an arbitrary
-    // /// Serializable value.
-    // ///
-    // /// NOTE: In Diem, we do not sign the raw bytes of a transaction, but
-    // /// those raw bytes prefixed by a domain separation hash.
-    // /// Informally signed_bytes = sha3(domain_separator) || bcs_serialization_bytes
-    // ///
-    // /// The domain separator hash is derived automatically from a `#[derive(CryptoHasher,
-    // /// BCSCryptoHash)]` annotation, or can be declared manually in a process
-    // /// described in `diem_crypto::hash`.
-    // ///
-    // pub fn sign<T: CryptoHash + Serialize>(&self, msg: &T) -> Ed25519Signature {
-    //     self.private_key.sign(msg)
-    // }
+    /// Diem specific sign function that is capable of signing an arbitrary
+    /// Serializable value.
+    ///
+    /// NOTE: In Diem, we do not sign the raw bytes of a transaction, but
+    /// those raw bytes prefixed by a domain separation hash.
+    /// Informally signed_bytes = sha3(domain_separator) || bcs_serialization_bytes
+    ///
+    /// The domain separator hash is derived automatically from a `#[derive(CryptoHasher,
+    /// BCSCryptoHash)]` annotation, or can be declared manually in a process
+    /// described in `diem_crypto::hash`.
+    ///
+    pub fn sign<T: CryptoHash + Serialize>(&self, msg: &T) -> Ed25519Signature {
+        self.private_key.sign(msg)
+    }
+
+    /// Sign raw bytes as they are, without a domain separator, e.g. a challenge
+    /// nonce to prove ownership of the key.
+    ///
+    /// NOTE: do not use this for transactions, use `sign`.
+    pub fn sign_arbitrary_bytes(&self, msg: &[u8]) -> Ed25519Signature {
+        self.private_key.sign_arbitrary_message(msg)
+    }
 }
 
 /// Wrapper struct from which we derive child keys
@@ -162,7 +186,18 @@ impl KeyFactory {
     /// Instantiate a new KeyFactor from a Seed, where the [u8; 64] raw bytes of the Seed are used
     /// to derive both the Main and its child keys
     pub fn new(seed: &Seed) -> Result<Self> {
-        let hkdf_extract = Hkdf::<Sha3_256>::extract(Some(KeyFactory::MAIN_KEY_SALT), &seed.0)?;
+        Self::new_with_salt(seed, "")
+    }
+
+    /// Instantiate a new KeyFactory from a Seed, mixing `extra_salt` into the HKDF extract
+    /// step, i.e. the salt is `MAIN_KEY_SALT || extra_salt`. This separates accounts of the
+    /// same mnemonic like a BIP39 passphrase does: every salt yields a completely independent
+    /// key tree, and nothing about one tree can be learned from another.
+    /// An empty `extra_salt` gives the same keys as `new`.
+    pub fn new_with_salt(seed: &Seed, extra_salt: &str) -> Result<Self> {
+        let mut salt = KeyFactory::MAIN_KEY_SALT.to_vec();
+        salt.extend_from_slice(extra_salt.as_bytes());
+        let hkdf_extract = Hkdf::<Sha3_256>::extract(Some(&salt), &seed.0)?;
 
         Ok(Self {
             main: Main::from(&hkdf_extract[..32]),
@@ -193,6 +228,59 @@ impl KeyFactory {
         })?;
         Ok(ExtendedPrivKey::new(child, sk))
     }
+
+    /// Derive `count` consecutive child keys starting at `start`, the same keys
+    /// `private_child` gives for each ChildNumber.
+    /// Errors if a child number would break the invariant `<= u64::MAX / 2`.
+    pub fn derive_range(&self, start: u64, count: u64) -> Result<Vec<ExtendedPrivKey>> {
+        if count == 0 {
+            return Ok(vec![]);
+        }
+        let last = start
+            .checked_add(count - 1)
+            .filter(|n| *n <= u64::MAX / 2)
+            .ok_or_else(|| {
+                anyhow!(
+                    "child numbers {}..{} exceed the maximum of {}",
+                    start,
+                    start.saturating_add(count),
+                    u64::MAX / 2
+                )
+            })?;
+
+        (start..=last)
+            .map(|n| self.private_child(ChildNumber::new(n)))
+            .collect()
+    }
+
+    /// Derive the PrivateKey of a key within an account, so that one mnemonic can
+    /// hold several separate accounts, like the `m/account'/key'` levels of BIP44.
+    ///
+    /// The HKDF info is `INFO_PREFIX || le_u64(account_index) || le_u64(key_index)`.
+    /// `private_child` appends a single u64, so the 16 byte suffix here never
+    /// collides with it, and the keys from `private_child` are unchanged.
+    pub fn derive_account(&self, account_index: u64, key_index: u64) -> Result<ExtendedPrivKey> {
+        let mut le_n = [0u8; 16];
+        LittleEndian::write_u64(&mut le_n[..8], account_index);
+        LittleEndian::write_u64(&mut le_n[8..], key_index);
+        let mut info = KeyFactory::INFO_PREFIX.to_vec();
+        info.extend_from_slice(&le_n);
+
+        let hkdf_expand = Hkdf::<Sha3_256>::expand(self.main(), Some(&info), 32)?;
+        let sk = Ed25519PrivateKey::try_from(hkdf_expand.as_slice()).map_err(|e| {
+            anyhow!(
+                "Unable to convert hkdf output into private key, met Error:{}",
+                e
+            )
+        })?;
+        Ok(ExtendedPrivKey {
+            key_path: KeyPath::Account {
+                account_index,
+                key_index,
+            },
+            private_key: sk,
+        })
+    }
 }
 
 /// Seed is the output of a one-way function, which accepts a Mnemonic as input
@@ -268,3 +356,186 @@ fn test_key_derivation() {
         hex::encode(&child_private_1_from_increment.private_key.to_bytes()[..])
     );
 }
+
+#[cfg(test)]
+#[test]
+fn test_account_derivation() {
+    let mnemonic = Mnemonic::from("legal winner thank year wave sausage worth useful legal winner thank year wave sausage worth useful legal will").unwrap();
+    let seed = Seed::new(&mnemonic, "DIEM");
+    let key_factory = KeyFactory::new(&seed).unwrap();
+
+    // Check account 0, key 0 derivation, which must not collide with child_0.
+    let account_0_key_0 = key_factory.derive_account(0, 0).unwrap();
+    assert_eq!(
+        "50a3ad1c2d9c8ffe0a20e32c019d785526a66dc7a217ed33795e6550c92fd8de",
+        hex::encode(&account_0_key_0.private_key.to_bytes()[..])
+    );
+    let child_private_0 = key_factory.private_child(ChildNumber(0)).unwrap();
+    assert_ne!(
+        hex::encode(&child_private_0.private_key.to_bytes()[..]),
+        hex::encode(&account_0_key_0.private_key.to_bytes()[..])
+    );
+
+    // Check determinism, regenerate account 0, key 0.
+    let account_0_key_0_again = key_factory.derive_account(0, 0).unwrap();
+    assert_eq!(
+        hex::encode(&account_0_key_0.private_key.to_bytes()[..]),
+        hex::encode(&account_0_key_0_again.private_key.to_bytes()[..])
+    );
+
+    // Check account 0, key 1 derivation.
+    let account_0_key_1 = key_factory.derive_account(0, 1).unwrap();
+    assert_eq!(
+        "b35d740f9d8a687c4029d45912ed11d832f82ed7b8bda21e911cdd5460200f0f",
+        hex::encode(&account_0_key_1.private_key.to_bytes()[..])
+    );
+
+    // Check account 1, key 0 derivation, the indices are not interchangeable.
+    let account_1_key_0 = key_factory.derive_account(1, 0).unwrap();
+    assert_eq!(
+        "ca881b0d03d8e332d4a19ea3546c7a1a1c038b2ff69fafe994eb20334e2cfe7d",
+        hex::encode(&account_1_key_0.private_key.to_bytes()[..])
+    );
+    assert_eq!(
+        account_1_key_0.key_path(),
+        KeyPath::Account {
+            account_index: 1,
+            key_index: 0
+        }
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_ol_legacy_address() {
+    // alice
+    let mnemonic = Mnemonic::from("talent sunset lizard pill fame nuclear spy noodle basket okay critic grow sleep legend hurry pitch blanket clerk impose rough degree sock insane purse").unwrap();
+    let seed = Seed::new(&mnemonic, "0L");
+    let key_factory = KeyFactory::new(&seed).unwrap();
+    let owner = key_factory.private_child(ChildNumber(0)).unwrap();
+
+    assert_eq!(
+        owner.get_address().to_string(),
+        "87515d94a244235a1433d7117bc0cb154c613c2f4b1e67ca8d98a542ee3f59f5"
+    );
+    assert_eq!(
+        owner.get_ol_legacy_address().to_string(),
+        "000000000000000000000000000000004c613c2f4b1e67ca8d98a542ee3f59f5"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_sign_round_trip() {
+    use diem_crypto::{traits::Signature, HashValue};
+    use diem_types::{block_info::BlockInfo, ledger_info::LedgerInfo};
+
+    let mnemonic = Mnemonic::from("legal winner thank year wave sausage worth useful legal winner thank year wave sausage worth useful legal will").unwrap();
+    let seed = Seed::new(&mnemonic, "DIEM");
+    let key_factory = KeyFactory::new(&seed).unwrap();
+    let child_private_0 = key_factory.private_child(ChildNumber(0)).unwrap();
+
+    let msg = LedgerInfo::new(BlockInfo::empty(), HashValue::zero());
+    let signature = child_private_0.sign(&msg);
+    assert!(signature
+        .verify(&msg, &child_private_0.get_public())
+        .is_ok());
+
+    // a signature does not verify with another key
+    let child_private_1 = key_factory.private_child(ChildNumber(1)).unwrap();
+    assert!(signature
+        .verify(&msg, &child_private_1.get_public())
+        .is_err());
+
+    // raw bytes, such as a challenge nonce
+    let nonce = b"0L proof of ownership nonce 42";
+    let signature = child_private_0.sign_arbitrary_bytes(nonce);
+    assert!(signature
+        .verify_arbitrary_msg(nonce, &child_private_0.get_public())
+        .is_ok());
+    assert!(signature
+        .verify_arbitrary_msg(b"another nonce", &child_private_0.get_public())
+        .is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_derive_range() {
+    let mnemonic = Mnemonic::from("legal winner thank year wave sausage worth useful legal winner thank year wave sausage worth useful legal will").unwrap();
+    let seed = Seed::new(&mnemonic, "DIEM");
+    let key_factory = KeyFactory::new(&seed).unwrap();
+
+    // Check the range matches deriving each child on its own.
+    let keys = key_factory.derive_range(0, 3).unwrap();
+    assert_eq!(keys.len(), 3);
+    for (n, key) in keys.iter().enumerate() {
+        let child = key_factory.private_child(ChildNumber(n as u64)).unwrap();
+        assert_eq!(
+            hex::encode(&child.private_key.to_bytes()[..]),
+            hex::encode(&key.private_key.to_bytes()[..])
+        );
+    }
+    assert_eq!(
+        "898e7adc4234c6d905c7145a97f288ed9891690b9cf02f05ec0177f85e763bb5",
+        hex::encode(&keys[1].private_key.to_bytes()[..])
+    );
+
+    // Check a range which does not start at zero.
+    let keys = key_factory.derive_range(1, 1).unwrap();
+    assert_eq!(
+        "898e7adc4234c6d905c7145a97f288ed9891690b9cf02f05ec0177f85e763bb5",
+        hex::encode(&keys[0].private_key.to_bytes()[..])
+    );
+
+    assert!(key_factory.derive_range(5, 0).unwrap().is_empty());
+
+    // Child numbers past u64::MAX / 2 are an error, not a panic.
+    assert!(key_factory.derive_range(u64::MAX / 2, 1).is_ok());
+    assert!(key_factory.derive_range(u64::MAX / 2, 2).is_err());
+    assert!(key_factory.derive_range(u64::MAX, 2).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_key_factory_with_salt() {
+    let mnemonic = Mnemonic::from("legal winner thank year wave sausage worth useful legal winner thank year wave sausage worth useful legal will").unwrap();
+    let seed = Seed::new(&mnemonic, "DIEM");
+
+    // An empty salt is the same as the default.
+    let key_factory = KeyFactory::new_with_salt(&seed, "").unwrap();
+    assert_eq!(
+        "86ebb495902d5638a8a7ea19575157ccc646dcbb2ec0b32037ee8f0cb3cb5582",
+        hex::encode(key_factory.main())
+    );
+
+    // Check each salt gives its own main key.
+    let alice = KeyFactory::new_with_salt(&seed, "alice").unwrap();
+    assert_eq!(
+        "3561225e4c8e529ac2adddd01803b3d5954dc8937823d3933420649b6e962d98",
+        hex::encode(alice.main())
+    );
+    let bob = KeyFactory::new_with_salt(&seed, "bob").unwrap();
+    assert_eq!(
+        "38bddb8e322049e00d9fee935cf23713f165c5502ce87de0524b92908f3a5891",
+        hex::encode(bob.main())
+    );
+
+    // Check determinism, regenerate with the same salt.
+    let alice_again = KeyFactory::new_with_salt(&seed, "alice").unwrap();
+    assert_eq!(
+        hex::encode(
+            &alice
+                .private_child(ChildNumber(0))
+                .unwrap()
+                .private_key
+                .to_bytes()[..]
+        ),
+        hex::encode(
+            &alice_again
+                .private_child(ChildNumber(0))
+                .unwrap()
+                .private_key
+                .to_bytes()[..]
+        )
+    );
+}
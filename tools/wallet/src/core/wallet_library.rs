@@ -42,7 +42,14 @@ impl WalletLibrary {
 
     /// Constructor that instantiates a new WalletLibrary from Mnemonic
     pub fn new_from_mnemonic(mnemonic: Mnemonic) -> Self {
-        let seed = Seed::new(&mnemonic, "0L"); //////// 0L ////////
+        Self::new_from_mnemonic_with_passphrase(mnemonic, None)
+    }
+
+    /// Constructor from a Mnemonic and an optional passphrase, which is used as
+    /// the seed salt in place of the default. The same mnemonic with another
+    /// passphrase gives unrelated keys.
+    pub fn new_from_mnemonic_with_passphrase(mnemonic: Mnemonic, passphrase: Option<&str>) -> Self {
+        let seed = Seed::new(&mnemonic, passphrase.unwrap_or("0L")); //////// 0L ////////
         WalletLibrary {
             mnemonic,
             key_factory: KeyFactory::new(&seed).unwrap(),
@@ -6,7 +6,8 @@
 
 use crate::{
     account_keys::{
-        get_keys_from_mnem, get_keys_from_prompt, get_ol_legacy_address, legacy_keygen, KeyChain,
+        get_keys_from_mnem_with_passphrase, get_keys_from_prompt_with_passphrase,
+        get_ol_legacy_address, legacy_keygen, KeyChain,
     },
     utils::{
         check_if_file_exists, create_dir_if_not_exist, dir_default_to_current, prompt_yes, to_yaml,
@@ -76,7 +77,7 @@ pub fn refresh_validator_files(
     KeyChain,
 )> {
     let (validator_blob, vfn_blob, private_identity, public_identity, legacy_keys) =
-        make_validator_keys(mnem, keep_legacy_addr)?;
+        make_validator_keys(mnem, None, keep_legacy_addr)?;
 
     save_val_files(
         output_opt,
@@ -95,9 +96,10 @@ pub fn refresh_validator_files(
     ))
 }
 
-/// create all the validator key structs from mnemonic
+/// create all the validator key structs from mnemonic, and the passphrase it was created with
 pub fn make_validator_keys(
     mnem: Option<String>,
+    passphrase: Option<&str>,
     keep_legacy_addr: bool,
 ) -> anyhow::Result<(
     IdentityBlob,
@@ -107,9 +109,9 @@ pub fn make_validator_keys(
     KeyChain,
 )> {
     let mut legacy_keys = if let Some(m) = mnem {
-        get_keys_from_mnem(m)?
+        get_keys_from_mnem_with_passphrase(m, passphrase)?
     } else {
-        get_keys_from_prompt()?
+        get_keys_from_prompt_with_passphrase(passphrase)?
     };
 
     if keep_legacy_addr {
@@ -6,6 +6,7 @@ pub mod core;
 pub mod key_gen;
 pub mod keys;
 pub mod load_keys;
+pub mod scan_accounts;
 pub mod utils;
 pub mod validator_files;
 pub mod wallet_cli;
@@ -11,7 +11,18 @@ use std::{env, process::exit};
 pub fn get_account_from_mnem(
     mnemonic_string: String,
 ) -> Result<(AuthenticationKey, AccountAddress, WalletLibrary), anyhow::Error> {
-    let mut wallet = WalletLibrary::new_from_mnemonic(Mnemonic::from(mnemonic_string.trim())?);
+    get_account_from_mnem_with_passphrase(mnemonic_string, None)
+}
+
+/// Get authkey and account from mnemonic, and the passphrase it was created with
+pub fn get_account_from_mnem_with_passphrase(
+    mnemonic_string: String,
+    passphrase: Option<&str>,
+) -> Result<(AuthenticationKey, AccountAddress, WalletLibrary), anyhow::Error> {
+    let mut wallet = WalletLibrary::new_from_mnemonic_with_passphrase(
+        Mnemonic::from(mnemonic_string.trim())?,
+        passphrase,
+    );
     let (auth_key, _) = wallet.new_address()?;
     let account = auth_key.derived_address();
     Ok((auth_key, account, wallet))
@@ -26,6 +37,13 @@ pub fn get_account_from_wallet(
 
 /// Prompts user to type mnemonic securely.
 pub fn get_account_from_prompt() -> (AuthenticationKey, AccountAddress, WalletLibrary) {
+    get_account_from_prompt_with_passphrase(None)
+}
+
+/// Prompts user to type mnemonic securely, the keys are derived with the passphrase if given.
+pub fn get_account_from_prompt_with_passphrase(
+    passphrase: Option<&str>,
+) -> (AuthenticationKey, AccountAddress, WalletLibrary) {
     println!("Enter your 0L mnemonic:");
 
     let test_env_mnem = env::var("MNEM");
@@ -47,7 +65,7 @@ pub fn get_account_from_prompt() -> (AuthenticationKey, AccountAddress, WalletLi
         },
     };
 
-    match get_account_from_mnem(mnem) {
+    match get_account_from_mnem_with_passphrase(mnem, passphrase) {
         Ok(a) => a,
         Err(e) => {
             println!(
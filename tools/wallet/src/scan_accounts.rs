@@ -0,0 +1,53 @@
+//! Recover the set of on-chain accounts derived from one mnemonic.
+
+use crate::core::{key_factory::ChildNumber, wallet_library::WalletLibrary};
+use anyhow::Result;
+use libra_query::account_queries::{account_exists, get_account_balance_libra};
+use libra_types::{
+    exports::{AccountAddress, Client},
+    move_resource::gas_coin::LibraBalanceDisplay,
+};
+use serde::Serialize;
+
+/// An account derived from the mnemonic which exists on chain.
+#[derive(Debug, Serialize)]
+pub struct ScannedAccount {
+    /// The child number used to derive the key
+    pub child: u64,
+    /// The address derived from the child key
+    pub account: AccountAddress,
+    /// The balance, scaled to coin decimals
+    pub balance: LibraBalanceDisplay,
+}
+
+/// Derive child keys sequentially and check each address on chain.
+/// Like a BIP44 gap limit, the scan stops after `gap` consecutive children
+/// which have no account on chain.
+pub async fn scan_accounts(
+    client: &Client,
+    wallet: &WalletLibrary,
+    gap: u64,
+) -> Result<Vec<ScannedAccount>> {
+    let kf = wallet.get_key_factory();
+    let mut found = vec![];
+    let mut unused = 0;
+    let mut child = ChildNumber::new(0);
+
+    while unused < gap {
+        let account = kf.private_child(child)?.get_address();
+        if account_exists(client, account).await? {
+            let balance = get_account_balance_libra(client, account).await?;
+            found.push(ScannedAccount {
+                child: *child.as_ref(),
+                account,
+                balance: balance.scaled(),
+            });
+            unused = 0;
+        } else {
+            unused += 1;
+        }
+        child.increment();
+    }
+
+    Ok(found)
+}
@@ -1,8 +1,10 @@
-use crate::{account_keys, whoami::who_am_i};
+use crate::{account_keys, load_keys, scan_accounts::scan_accounts, whoami::who_am_i};
 
 use anyhow::Result;
 use clap::{Args, Parser, Subcommand};
+use libra_types::{exports::Client, type_extensions::client_ext::ClientExt};
 use std::path::PathBuf;
+use url::Url;
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
@@ -29,6 +31,8 @@ enum WalletSub {
     Legacy,
     /// use mnemonic to see what account keys are generated
     Whoami(WhoamiOpts),
+    /// find all on-chain accounts derived from a mnemonic
+    ScanAccounts(ScanAccountsOpts),
 }
 
 #[derive(Args, Debug)]
@@ -43,6 +47,28 @@ struct WhoamiOpts {
 
     #[clap(short('m'), long)]
     mnemonic: Option<String>,
+
+    /// optional, the passphrase the mnemonic's keys were created with
+    #[clap(long)]
+    passphrase: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct ScanAccountsOpts {
+    /// stop after this many consecutive derived accounts are not found on chain
+    #[clap(short, long, default_value = "5")]
+    gap: u64,
+
+    #[clap(short('m'), long)]
+    mnemonic: Option<String>,
+
+    /// optional, the passphrase the mnemonic's keys were created with
+    #[clap(long)]
+    passphrase: Option<String>,
+
+    /// optional, URL of the upstream node, otherwise will use the config file
+    #[clap(short, long)]
+    url: Option<Url>,
 }
 
 impl WalletCli {
@@ -52,9 +78,26 @@ impl WalletCli {
                 who_am_i(
                     args.legacy_address,
                     args.mnemonic.clone(),
+                    args.passphrase.as_deref(),
                     args.show_validator,
                 )?;
             }
+            WalletSub::ScanAccounts(args) => {
+                let passphrase = args.passphrase.as_deref();
+                let (_, _, wallet) = match &args.mnemonic {
+                    Some(m) => {
+                        load_keys::get_account_from_mnem_with_passphrase(m.to_owned(), passphrase)?
+                    }
+                    None => load_keys::get_account_from_prompt_with_passphrase(passphrase),
+                };
+                let client = match &args.url {
+                    Some(u) => Client::new(u.clone()),
+                    None => Client::default().await?,
+                };
+
+                let found = scan_accounts(&client, &wallet, args.gap).await?;
+                println!("{}", serde_json::to_string_pretty(&found)?);
+            }
             WalletSub::Legacy => {
                 println!("this command will generate legacy keys and addresses from v5 addresses. You should only be using this for testing or debugging purposes");
 
@@ -5,6 +5,7 @@ use dialoguer::Confirm;
 pub fn who_am_i(
     legacy_address_opt: bool,
     mnemonic: Option<String>,
+    passphrase: Option<&str>,
     show_validator: bool,
 ) -> anyhow::Result<()> {
     let keep_legacy_address = legacy_address_opt
@@ -16,7 +17,7 @@ pub fn who_am_i(
     // info
     // the owner key will derive to the same.
     let (_validator_blob, _vfn_blob, _private_identity, public_identity, _legacy_keys) =
-        make_validator_keys(mnemonic, keep_legacy_address)?;
+        make_validator_keys(mnemonic, passphrase, keep_legacy_address)?;
 
     if show_validator {
         println!("validator public credentials:");
@@ -0,0 +1,45 @@
+use libra_smoke_tests::libra_smoke::LibraSmoke;
+use libra_types::core_types::fixtures::TestPersona;
+use libra_wallet::{
+    core::key_factory::ChildNumber, load_keys::get_account_from_mnem, scan_accounts::scan_accounts,
+};
+
+/// Fund two derived accounts with a hole between them, and check the scan
+/// finds both and stops once the gap limit is reached.
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn scan_finds_funded_children() -> anyhow::Result<()> {
+    let mut s = LibraSmoke::new(None, None)
+        .await
+        .expect("could not start swarm");
+
+    let mnem = TestPersona::Bob.get_persona_mnem();
+    let (_, _, wallet) = get_account_from_mnem(mnem)?;
+    let kf = wallet.get_key_factory();
+
+    let child_0 = kf.private_child(ChildNumber::new(0))?.get_address();
+    let child_2 = kf.private_child(ChildNumber::new(2))?.get_address();
+    let child_5 = kf.private_child(ChildNumber::new(5))?.get_address();
+
+    s.mint_and_unlock(child_0, 10_000_000).await?;
+    s.mint_and_unlock(child_2, 20_000_000).await?;
+    // beyond the gap limit of 2 after child 2, should never be reached
+    s.mint_and_unlock(child_5, 30_000_000).await?;
+
+    let found = scan_accounts(&s.client(), &wallet, 2).await?;
+
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].account, child_0);
+    assert_eq!(found[0].child, 0);
+    assert_eq!(found[0].balance.total, 10.0);
+    assert_eq!(found[1].account, child_2);
+    assert_eq!(found[1].child, 2);
+    assert_eq!(found[1].balance.total, 20.0);
+
+    // a wider gap reaches past the hole to child 5
+    let found = scan_accounts(&s.client(), &wallet, 3).await?;
+    assert_eq!(found.len(), 3);
+    assert_eq!(found[2].account, child_5);
+    assert_eq!(found[2].child, 5);
+
+    Ok(())
+}
@@ -13,6 +13,8 @@ pub enum TestPersona {
     Bob,
     Carol,
     Dave,
+    Eve,
+    Frank,
 }
 
 impl FromStr for TestPersona {
@@ -25,6 +27,8 @@ impl FromStr for TestPersona {
             "bob" => Ok(TestPersona::Bob),
             "carol" => Ok(TestPersona::Carol),
             "dave" => Ok(TestPersona::Dave),
+            "eve" => Ok(TestPersona::Eve),
+            "frank" => Ok(TestPersona::Frank),
             _ => Err("not found"),
         }
     }
@@ -38,20 +42,32 @@ impl fmt::Display for TestPersona {
             TestPersona::Bob => "bob",
             TestPersona::Carol => "carol",
             TestPersona::Dave => "dave",
+            TestPersona::Eve => "eve",
+            TestPersona::Frank => "frank",
         };
         write!(f, "{}", s)
     }
 }
 
 impl TestPersona {
+    /// every persona, in validator seat order
+    pub const ALL: [TestPersona; 6] = [
+        TestPersona::Alice,
+        TestPersona::Bob,
+        TestPersona::Carol,
+        TestPersona::Dave,
+        TestPersona::Eve,
+        TestPersona::Frank,
+    ];
+
     /// get persona from index. Used for testnet to assign persona to validator seat
     pub fn from(idx: usize) -> anyhow::Result<Self> {
-        match idx {
-            0 => Ok(TestPersona::Alice),
-            1 => Ok(TestPersona::Bob),
-            2 => Ok(TestPersona::Carol),
-            3 => Ok(TestPersona::Dave),
-            _ => bail!("no default persona at this index"),
+        match Self::ALL.get(idx) {
+            Some(p) => Ok(p.clone()),
+            None => {
+                let names: Vec<String> = Self::ALL.iter().map(|p| p.to_string()).collect();
+                bail!("no test persona for index {idx} (only {})", names.join("/"))
+            }
         }
     }
 
@@ -62,6 +78,8 @@ impl TestPersona {
             TestPersona::Bob => 1,
             TestPersona::Carol => 2,
             TestPersona::Dave => 3,
+            TestPersona::Eve => 4,
+            TestPersona::Frank => 5,
         }
     }
     pub fn get_persona_mnem(&self) -> String {
@@ -69,12 +87,31 @@ impl TestPersona {
         TestPersona::Alice => "talent sunset lizard pill fame nuclear spy noodle basket okay critic grow sleep legend hurry pitch blanket clerk impose rough degree sock insane purse",
         TestPersona::Bob => "ring pumpkin cake build jungle cloth bronze aerobic mechanic baby love melt below sight cotton trophy inquiry sugar exhibit sure first match ten clarify",
         TestPersona::Carol => "giraffe tower toward rapid flower obey piano circle better announce castle when enlist inquiry arrive segment leave develop confirm avoid meat loud fit parent",
-        TestPersona::Dave => "recall october regret kite undo choice outside season business wall quit arrest vacant arrow giggle vote ghost winter hawk soft cheap decide exhaust spare"
+        TestPersona::Dave => "recall october regret kite undo choice outside season business wall quit arrest vacant arrow giggle vote ghost winter hawk soft cheap decide exhaust spare",
+        TestPersona::Eve => "wise method donate awake luggage stamp empower where wrist future system flame snack width wish result useless mention assist dice sock wood inhale fit",
+        TestPersona::Frank => "type menu begin purse glow side panel mean dragon jar radio ethics salt dentist bring primary quick sense fuel lounge height clever cart color"
       };
         s.to_string()
     }
 }
 
+#[test]
+fn test_persona_from_index() {
+    let names = ["alice", "bob", "carol", "dave", "eve", "frank"];
+    for (i, name) in names.iter().enumerate() {
+        let p = TestPersona::from(i).unwrap();
+        assert_eq!(&p.to_string(), name);
+        assert_eq!(p.idx(), i);
+        assert_eq!(p.to_string().parse::<TestPersona>().unwrap().idx(), i);
+        assert_eq!(p.get_persona_mnem().split(' ').count(), 24);
+    }
+    let err = TestPersona::from(6).unwrap_err().to_string();
+    assert_eq!(
+        err,
+        "no test persona for index 6 (only alice/bob/carol/dave/eve/frank)"
+    );
+}
+
 #[test]
 fn test_block() {
     let mnem = TestPersona::Alice.get_persona_mnem();
@@ -111,6 +111,16 @@ impl SlowWalletBalance {
             total: cast_coin_to_decimal(self.total),
         }
     }
+
+    /// scale by the decimals the coin reports on chain, instead of the
+    /// compiled in precision
+    pub fn scaled_with_decimals(&self, decimals: u8) -> LibraBalanceDisplay {
+        let factor = 10f64.powi(decimals as i32);
+        LibraBalanceDisplay {
+            unlocked: self.unlocked as f64 / factor,
+            total: self.total as f64 / factor,
+        }
+    }
 }
 
 /// This is the same shape as Slow Wallet balance, except that it is scaled.
@@ -61,6 +61,7 @@ pub async fn upgrade_multiple_impl(
         tx_profile: None,
         tx_cost: Some(TxCost::framework_upgrade()),
         estimate_only: false,
+        error_json: false,
         legacy_address: false,
     };
 